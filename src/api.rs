@@ -0,0 +1,231 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::{
+    chat_completion::{request::ChatCompletionRequest, response::ChatResponse},
+    client::OllamaClient,
+    completion::{request::CompletionRequest, response::CompletionResponse},
+    control::RequestControl,
+    errors::OllamaError,
+    model::{
+        copy::CopyModelRequest,
+        create::{CreateModelRequest, CreateModelResponse},
+        delete::DeleteModelRequest,
+        generate_embeddings::{EmbeddingsRequest, EmbeddingsResponse},
+        list_local::ListLocalModelsResponse,
+        list_running::ListRunningModelsResponse,
+        pull::{PullModelRequest, PullModelResponse},
+        push::{PushModelRequest, PushModelResponse},
+        show_info::{ShowModelRequest, ShowModelResponse},
+    },
+    response::OllamaResponse,
+};
+
+/// The Ollama API, implemented by [`OllamaClient`]. Depend on this trait instead of
+/// the client to swap in fakes, wrappers (caching, logging, rate limiting) or
+/// alternative transports without changing call sites.
+#[async_trait]
+pub trait OllamaApi: Send + Sync {
+    async fn chat_with_control(
+        &self,
+        request: ChatCompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<ChatResponse>, OllamaError>;
+
+    async fn chat(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<OllamaResponse<ChatResponse>, OllamaError> {
+        self.chat_with_control(request, RequestControl::default())
+            .await
+    }
+
+    async fn completion_with_control(
+        &self,
+        request: CompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<CompletionResponse>, OllamaError>;
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<OllamaResponse<CompletionResponse>, OllamaError> {
+        self.completion_with_control(request, RequestControl::default())
+            .await
+    }
+
+    async fn generate_embeddings(
+        &self,
+        request: EmbeddingsRequest,
+    ) -> Result<EmbeddingsResponse, OllamaError>;
+
+    async fn create(
+        &self,
+        request: CreateModelRequest,
+    ) -> Result<OllamaResponse<CreateModelResponse>, OllamaError>;
+
+    async fn list_local(&self) -> Result<ListLocalModelsResponse, OllamaError>;
+
+    async fn list_running(&self) -> Result<ListRunningModelsResponse, OllamaError>;
+
+    async fn show_info(&self, request: ShowModelRequest) -> Result<ShowModelResponse, OllamaError>;
+
+    async fn copy(&self, request: CopyModelRequest) -> Result<(), OllamaError>;
+
+    async fn delete(&self, request: DeleteModelRequest) -> Result<(), OllamaError>;
+
+    async fn pull(
+        &self,
+        request: PullModelRequest,
+    ) -> Result<OllamaResponse<PullModelResponse>, OllamaError>;
+
+    async fn push(
+        &self,
+        request: PushModelRequest,
+    ) -> Result<OllamaResponse<PushModelResponse>, OllamaError>;
+}
+
+#[async_trait]
+impl OllamaApi for OllamaClient {
+    async fn chat_with_control(
+        &self,
+        request: ChatCompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<ChatResponse>, OllamaError> {
+        OllamaClient::chat_with_control(self, request, control).await
+    }
+
+    async fn completion_with_control(
+        &self,
+        request: CompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<CompletionResponse>, OllamaError> {
+        OllamaClient::completion_with_control(self, request, control).await
+    }
+
+    async fn generate_embeddings(
+        &self,
+        request: EmbeddingsRequest,
+    ) -> Result<EmbeddingsResponse, OllamaError> {
+        OllamaClient::generate_embeddings(self, request).await
+    }
+
+    async fn create(
+        &self,
+        request: CreateModelRequest,
+    ) -> Result<OllamaResponse<CreateModelResponse>, OllamaError> {
+        OllamaClient::create(self, request).await
+    }
+
+    async fn list_local(&self) -> Result<ListLocalModelsResponse, OllamaError> {
+        OllamaClient::list_local(self).await
+    }
+
+    async fn list_running(&self) -> Result<ListRunningModelsResponse, OllamaError> {
+        OllamaClient::list_running(self).await
+    }
+
+    async fn show_info(&self, request: ShowModelRequest) -> Result<ShowModelResponse, OllamaError> {
+        OllamaClient::show_info(self, request).await
+    }
+
+    async fn copy(&self, request: CopyModelRequest) -> Result<(), OllamaError> {
+        OllamaClient::copy(self, request).await
+    }
+
+    async fn delete(&self, request: DeleteModelRequest) -> Result<(), OllamaError> {
+        OllamaClient::delete(self, request).await
+    }
+
+    async fn pull(
+        &self,
+        request: PullModelRequest,
+    ) -> Result<OllamaResponse<PullModelResponse>, OllamaError> {
+        OllamaClient::pull(self, request).await
+    }
+
+    async fn push(
+        &self,
+        request: PushModelRequest,
+    ) -> Result<OllamaResponse<PushModelResponse>, OllamaError> {
+        OllamaClient::push(self, request).await
+    }
+}
+
+/// Forward every call to the shared implementation, so that `Arc<dyn OllamaApi>`
+/// can be passed wherever an [`OllamaApi`] is expected.
+#[async_trait]
+impl<A: OllamaApi + ?Sized> OllamaApi for Arc<A> {
+    async fn chat_with_control(
+        &self,
+        request: ChatCompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<ChatResponse>, OllamaError> {
+        (**self).chat_with_control(request, control).await
+    }
+
+    async fn completion_with_control(
+        &self,
+        request: CompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<CompletionResponse>, OllamaError> {
+        (**self).completion_with_control(request, control).await
+    }
+
+    async fn generate_embeddings(
+        &self,
+        request: EmbeddingsRequest,
+    ) -> Result<EmbeddingsResponse, OllamaError> {
+        (**self).generate_embeddings(request).await
+    }
+
+    async fn create(
+        &self,
+        request: CreateModelRequest,
+    ) -> Result<OllamaResponse<CreateModelResponse>, OllamaError> {
+        (**self).create(request).await
+    }
+
+    async fn list_local(&self) -> Result<ListLocalModelsResponse, OllamaError> {
+        (**self).list_local().await
+    }
+
+    async fn list_running(&self) -> Result<ListRunningModelsResponse, OllamaError> {
+        (**self).list_running().await
+    }
+
+    async fn show_info(&self, request: ShowModelRequest) -> Result<ShowModelResponse, OllamaError> {
+        (**self).show_info(request).await
+    }
+
+    async fn copy(&self, request: CopyModelRequest) -> Result<(), OllamaError> {
+        (**self).copy(request).await
+    }
+
+    async fn delete(&self, request: DeleteModelRequest) -> Result<(), OllamaError> {
+        (**self).delete(request).await
+    }
+
+    async fn pull(
+        &self,
+        request: PullModelRequest,
+    ) -> Result<OllamaResponse<PullModelResponse>, OllamaError> {
+        (**self).pull(request).await
+    }
+
+    async fn push(
+        &self,
+        request: PushModelRequest,
+    ) -> Result<OllamaResponse<PushModelResponse>, OllamaError> {
+        (**self).push(request).await
+    }
+}
+
+/// The key of a request: its path and canonical serialized body.
+pub(crate) fn request_key(path: &str, request: &impl Serialize) -> Result<String, OllamaError> {
+    // Objects are serialized with sorted keys.
+    let body = serde_json::to_value(request).map_err(|e| OllamaError::ParseError(e.to_string()))?;
+    Ok(format!("{path} {body}"))
+}
@@ -0,0 +1,59 @@
+use std::process::ExitCode;
+
+use clap::Parser;
+use pure_ollama::{
+    client::{OllamaClient, DEFAULT_HOST},
+    openai::proxy,
+};
+use tokio::net::TcpListener;
+
+/// Serve the OpenAI chat completions, embeddings and models endpoints
+/// backed by an Ollama server.
+#[derive(Debug, Parser)]
+#[command(name = "pure-ollama-openai-proxy", version)]
+struct Args {
+    /// Address to listen on.
+    #[arg(
+        long,
+        env = "PURE_OLLAMA_PROXY_LISTEN",
+        default_value = "127.0.0.1:8080"
+    )]
+    listen: String,
+
+    /// Base URL of the Ollama server.
+    #[arg(long, env = "OLLAMA_HOST", default_value = DEFAULT_HOST)]
+    host: String,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    let host = match args.host.contains("://") {
+        true => args.host,
+        false => format!("http://{}", args.host),
+    };
+
+    let listener = match TcpListener::bind(&args.listen).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Error: cannot listen on {}: {e}", args.listen);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("Forwarding http://{}/v1 to {host}", args.listen);
+
+    let app = proxy::router(OllamaClient::new(host));
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    match axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown)
+        .await
+    {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
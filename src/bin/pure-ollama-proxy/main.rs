@@ -0,0 +1,79 @@
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::Parser;
+use pure_ollama::{
+    client::{OllamaClient, DEFAULT_HOST},
+    proxy::{Proxy, ProxyConfig},
+};
+use tokio::net::TcpListener;
+
+/// Serve the native Ollama API to the holders of an API key, enforcing
+/// per-key model allowlists, token quotas and concurrency limits.
+/// One JSON line is written to stdout per request with its usage.
+#[derive(Debug, Parser)]
+#[command(name = "pure-ollama-proxy", version)]
+struct Args {
+    /// JSON file holding the API keys and their policies.
+    #[arg(long, env = "PURE_OLLAMA_PROXY_KEYS")]
+    keys: PathBuf,
+
+    /// Address to listen on.
+    #[arg(
+        long,
+        env = "PURE_OLLAMA_PROXY_LISTEN",
+        default_value = "127.0.0.1:11435"
+    )]
+    listen: String,
+
+    /// Base URL of the Ollama server.
+    #[arg(long, env = "OLLAMA_HOST", default_value = DEFAULT_HOST)]
+    host: String,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    let host = match args.host.contains("://") {
+        true => args.host,
+        false => format!("http://{}", args.host),
+    };
+
+    let config = match ProxyConfig::load(&args.keys) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: cannot load {}: {e}", args.keys.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let listener = match TcpListener::bind(&args.listen).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Error: cannot listen on {}: {e}", args.listen);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!(
+        "Forwarding http://{} to {host} for {} API keys",
+        args.listen,
+        config.keys.len()
+    );
+
+    let proxy = Proxy::new(OllamaClient::new(host), config).on_usage(|record| {
+        if let Ok(line) = serde_json::to_string(record) {
+            println!("{line}");
+        }
+    });
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    match axum::serve(listener, proxy.router())
+        .with_graceful_shutdown(shutdown)
+        .await
+    {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
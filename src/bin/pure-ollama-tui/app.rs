@@ -0,0 +1,464 @@
+use std::{mem, path::PathBuf, time::Instant};
+
+use pure_ollama::{
+    chat_completion::{
+        message::{Message, Role},
+        response::ChatResponse,
+    },
+    client::OllamaClient,
+    control::{CancellationToken, RequestControlBuilder},
+    conversation::Conversation,
+    errors::OllamaError,
+    options::Options,
+};
+use ratatui::{
+    crossterm::event::{self, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    widgets::ListState,
+};
+use serde_json::Value;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio_stream::StreamExt;
+
+use crate::{io_error, ui, Term};
+
+pub(crate) enum Event {
+    Input(event::Event),
+
+    /// A chunk of the reply with the given id.
+    Chunk(u64, Result<Box<ChatResponse>, OllamaError>),
+
+    /// The stream of the reply with the given id has ended.
+    ReplyEnd(u64),
+
+    Models(Result<Vec<String>, OllamaError>),
+}
+
+/// The reply being streamed.
+pub(crate) struct Reply {
+    id: u64,
+    pub(crate) content: String,
+    pub(crate) chunks: usize,
+    pub(crate) started: Instant,
+    cancellation: CancellationToken,
+}
+
+impl Reply {
+    /// Estimated speed while streaming, one chunk being about one token.
+    pub(crate) fn tokens_per_second(&self) -> f64 {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        match elapsed > 0.0 {
+            true => self.chunks as f64 / elapsed,
+            false => 0.0,
+        }
+    }
+}
+
+pub(crate) enum Popup {
+    Models {
+        models: Option<Vec<String>>,
+        state: ListState,
+    },
+    Options {
+        state: ListState,
+        editing: Option<String>,
+    },
+}
+
+pub(crate) struct App {
+    client: OllamaClient,
+    pub(crate) conversation: Conversation,
+    save: Option<PathBuf>,
+    events: UnboundedSender<Event>,
+    pub(crate) input: String,
+    pub(crate) reply: Option<Reply>,
+    next_reply_id: u64,
+    pub(crate) popup: Option<Popup>,
+    /// First line of the conversation pane shown, ignored while following the end.
+    pub(crate) scroll: u16,
+    pub(crate) follow: bool,
+    pub(crate) notice: Option<String>,
+    pub(crate) tokens_per_second: Option<f64>,
+    quit: bool,
+}
+
+impl App {
+    pub(crate) fn new(
+        client: OllamaClient,
+        conversation: Conversation,
+        save: Option<PathBuf>,
+        events: UnboundedSender<Event>,
+    ) -> Self {
+        Self {
+            client,
+            conversation,
+            save,
+            events,
+            input: String::default(),
+            reply: None,
+            next_reply_id: 0,
+            popup: None,
+            scroll: 0,
+            follow: true,
+            notice: None,
+            tokens_per_second: None,
+            quit: false,
+        }
+    }
+
+    pub(crate) async fn run(
+        mut self,
+        terminal: &mut Term,
+        mut events: UnboundedReceiver<Event>,
+    ) -> Result<(), OllamaError> {
+        while !self.quit {
+            terminal
+                .draw(|frame| ui::draw(frame, &mut self))
+                .map_err(io_error)?;
+            match events.recv().await {
+                Some(event) => self.handle(event),
+                None => break,
+            }
+        }
+
+        if let Some(reply) = &self.reply {
+            reply.cancellation.cancel();
+        }
+        self.save()
+    }
+
+    fn handle(&mut self, event: Event) {
+        match event {
+            Event::Input(event::Event::Key(key)) if key.kind != KeyEventKind::Release => {
+                self.handle_key(key)
+            }
+            Event::Input(_) => {}
+            Event::Chunk(id, chunk) => self.handle_chunk(id, chunk),
+            Event::ReplyEnd(id) => {
+                if self.reply.as_ref().is_some_and(|reply| reply.id == id) {
+                    self.finish_reply(None);
+                }
+            }
+            Event::Models(result) => {
+                if let Some(Popup::Models { models, state }) = &mut self.popup {
+                    match result {
+                        Ok(names) => {
+                            let current = names.iter().position(|n| n == &self.conversation.model);
+                            state.select(current.or((!names.is_empty()).then_some(0)));
+                            *models = Some(names);
+                        }
+                        Err(e) => {
+                            self.notice = Some(e.to_string());
+                            self.popup = None;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        if ctrl && matches!(key.code, KeyCode::Char('c') | KeyCode::Char('q')) {
+            self.quit = true;
+            return;
+        }
+
+        match self.popup.take() {
+            Some(Popup::Models { models, state }) => self.handle_model_key(key, models, state),
+            Some(Popup::Options { state, editing }) => self.handle_options_key(key, state, editing),
+            None => self.handle_chat_key(key, ctrl),
+        }
+    }
+
+    fn handle_chat_key(&mut self, key: KeyEvent, ctrl: bool) {
+        match key.code {
+            KeyCode::Char('p') if ctrl => self.open_model_picker(),
+            KeyCode::Char('o') if ctrl => {
+                self.popup = Some(Popup::Options {
+                    state: ListState::default().with_selected(Some(0)),
+                    editing: None,
+                })
+            }
+            KeyCode::Char('l') if ctrl => {
+                self.conversation
+                    .messages
+                    .retain(|message| message.role == Role::System);
+                self.notice = Some(String::from("Cleared the conversation."));
+            }
+            KeyCode::Char('s') if ctrl => {
+                self.notice = Some(match (&self.save, self.save()) {
+                    (None, _) => String::from("Start with --save <path> to save the conversation."),
+                    (Some(path), Ok(())) => format!("Saved to {}.", path.display()),
+                    (Some(_), Err(e)) => e.to_string(),
+                });
+            }
+            KeyCode::Char('u') if ctrl => self.input.clear(),
+            KeyCode::Char(c) if !ctrl => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Enter => self.send(),
+            KeyCode::Esc => {
+                if let Some(reply) = &self.reply {
+                    reply.cancellation.cancel();
+                }
+            }
+            KeyCode::Up => self.scroll_by(-1),
+            KeyCode::Down => self.scroll_by(1),
+            KeyCode::PageUp => self.scroll_by(-10),
+            KeyCode::PageDown => self.scroll_by(10),
+            KeyCode::End => self.follow = true,
+            _ => {}
+        }
+    }
+
+    fn handle_model_key(
+        &mut self,
+        key: KeyEvent,
+        models: Option<Vec<String>>,
+        mut state: ListState,
+    ) {
+        let count = models.as_ref().map_or(0, Vec::len);
+        match key.code {
+            KeyCode::Esc => return,
+            KeyCode::Up => select_by(&mut state, count, -1),
+            KeyCode::Down => select_by(&mut state, count, 1),
+            KeyCode::Enter => {
+                let selected = state.selected().zip(models.as_ref());
+                if let Some(model) = selected.and_then(|(index, models)| models.get(index)) {
+                    self.conversation.model = model.clone();
+                    self.notice = Some(format!("Switched to {model}."));
+                    return;
+                }
+            }
+            _ => {}
+        }
+        self.popup = Some(Popup::Models { models, state });
+    }
+
+    fn handle_options_key(&mut self, key: KeyEvent, mut state: ListState, editing: Option<String>) {
+        let name = Options::names()[state.selected().unwrap_or_default()];
+
+        if let Some(mut value) = editing {
+            match key.code {
+                KeyCode::Esc => {
+                    self.popup = Some(Popup::Options {
+                        state,
+                        editing: None,
+                    });
+                    return;
+                }
+                KeyCode::Enter => {
+                    let value = match value.trim() {
+                        "" => "null",
+                        value => value,
+                    };
+                    if let Err(e) = self.conversation.options.set(name, value) {
+                        self.notice = Some(e.to_string());
+                    }
+                    self.popup = Some(Popup::Options {
+                        state,
+                        editing: None,
+                    });
+                    return;
+                }
+                KeyCode::Backspace => {
+                    value.pop();
+                }
+                KeyCode::Char(c) => value.push(c),
+                _ => {}
+            }
+            self.popup = Some(Popup::Options {
+                state,
+                editing: Some(value),
+            });
+            return;
+        }
+
+        let editing = match key.code {
+            KeyCode::Esc => return,
+            KeyCode::Up => {
+                select_by(&mut state, Options::names().len(), -1);
+                None
+            }
+            KeyCode::Down => {
+                select_by(&mut state, Options::names().len(), 1);
+                None
+            }
+            KeyCode::Enter => Some(self.option_value(name).unwrap_or_default()),
+            KeyCode::Delete | KeyCode::Backspace => {
+                let _ = self.conversation.options.set(name, "null");
+                None
+            }
+            _ => None,
+        };
+        self.popup = Some(Popup::Options { state, editing });
+    }
+
+    /// The value of the option called `name` as typed in the editor.
+    pub(crate) fn option_value(&self, name: &str) -> Option<String> {
+        self.conversation
+            .options
+            .get(name)
+            .map(|value| match value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            })
+    }
+
+    pub(crate) fn open_model_picker(&mut self) {
+        self.popup = Some(Popup::Models {
+            models: None,
+            state: ListState::default(),
+        });
+
+        let client = self.client.clone();
+        let events = self.events.clone();
+        tokio::spawn(async move {
+            let models = client.list_local().await.map(|response| {
+                response
+                    .models
+                    .into_iter()
+                    .map(|model| model.name)
+                    .collect()
+            });
+            let _ = events.send(Event::Models(models));
+        });
+    }
+
+    /// Replace the leading system message of the conversation.
+    pub(crate) fn set_system(&mut self, system: String) {
+        let message = Message {
+            role: Role::System,
+            content: system,
+            images: None,
+        };
+        let messages = &mut self.conversation.messages;
+        match messages.first().map(|message| &message.role) {
+            Some(Role::System) => messages[0] = message,
+            _ => messages.insert(0, message),
+        }
+    }
+
+    fn send(&mut self) {
+        if self.reply.is_some() || self.input.trim().is_empty() {
+            return;
+        }
+        if self.conversation.model.is_empty() {
+            self.open_model_picker();
+            return;
+        }
+
+        let prompt = mem::take(&mut self.input);
+        self.conversation.push_user(prompt.trim());
+        self.notice = None;
+        self.follow = true;
+
+        let id = self.next_reply_id;
+        self.next_reply_id += 1;
+        let cancellation = CancellationToken::new();
+        let control = RequestControlBuilder::default()
+            .cancellation(cancellation.clone())
+            .build()
+            .unwrap();
+        self.reply = Some(Reply {
+            id,
+            content: String::default(),
+            chunks: 0,
+            started: Instant::now(),
+            cancellation,
+        });
+
+        let client = self.client.clone();
+        let request = self.conversation.chat_request();
+        let events = self.events.clone();
+        tokio::spawn(async move {
+            let stream = match client.chat_with_control(request, control).await {
+                Ok(response) => response.as_stream().await,
+                Err(e) => Err(e),
+            };
+            match stream {
+                Ok(mut stream) => {
+                    while let Some(item) = stream.next().await {
+                        if events.send(Event::Chunk(id, item.map(Box::new))).is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(e) => {
+                    let _ = events.send(Event::Chunk(id, Err(e)));
+                }
+            }
+            let _ = events.send(Event::ReplyEnd(id));
+        });
+    }
+
+    fn handle_chunk(&mut self, id: u64, chunk: Result<Box<ChatResponse>, OllamaError>) {
+        let Some(reply) = self.reply.as_mut().filter(|reply| reply.id == id) else {
+            return;
+        };
+
+        match chunk {
+            Ok(chunk) => {
+                if let Some(message) = &chunk.message {
+                    reply.content += &message.content;
+                }
+                reply.chunks += 1;
+                if chunk.done {
+                    self.tokens_per_second = chunk.tokens_per_second();
+                }
+            }
+            Err(e) => self.finish_reply(Some(e)),
+        }
+    }
+
+    /// Record the reply in the conversation. A reply failing before any content
+    /// is dropped together with its prompt, which is put back in the input.
+    fn finish_reply(&mut self, error: Option<OllamaError>) {
+        let Some(reply) = self.reply.take() else {
+            return;
+        };
+
+        if let Some(e) = error {
+            self.notice = Some(match e {
+                OllamaError::Cancelled => String::from("Stopped."),
+                e => e.to_string(),
+            });
+        }
+
+        if reply.content.is_empty() {
+            if let Some(prompt) = self.conversation.messages.pop() {
+                if self.input.is_empty() {
+                    self.input = prompt.content;
+                }
+            }
+            return;
+        }
+
+        self.conversation.push(Message {
+            role: Role::Assistant,
+            content: reply.content,
+            images: None,
+        });
+    }
+
+    fn scroll_by(&mut self, delta: i16) {
+        self.follow = false;
+        self.scroll = self.scroll.saturating_add_signed(delta);
+    }
+
+    fn save(&self) -> Result<(), OllamaError> {
+        match &self.save {
+            Some(path) => self.conversation.save(path),
+            None => Ok(()),
+        }
+    }
+}
+
+fn select_by(state: &mut ListState, count: usize, delta: isize) {
+    if count == 0 {
+        return;
+    }
+    let selected = state.selected().unwrap_or_default() as isize + delta;
+    state.select(Some(selected.clamp(0, count as isize - 1) as usize));
+}
@@ -0,0 +1,130 @@
+use std::{
+    io::{self, Stdout},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use clap::Parser;
+use pure_ollama::{
+    client::{OllamaClient, DEFAULT_HOST},
+    conversation::Conversation,
+    errors::OllamaError,
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    crossterm::{
+        event,
+        terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+        ExecutableCommand,
+    },
+    Terminal,
+};
+use tokio::sync::mpsc;
+
+use crate::app::{App, Event};
+
+mod app;
+mod ui;
+
+/// Full-screen chat with an Ollama server.
+#[derive(Debug, Parser)]
+#[command(name = "pure-ollama-tui", version)]
+struct Args {
+    /// The model to chat with, picked from the local models when omitted.
+    model: Option<String>,
+
+    /// Base URL of the Ollama server.
+    #[arg(long, env = "OLLAMA_HOST", default_value = DEFAULT_HOST)]
+    host: String,
+
+    /// System message of the conversation.
+    #[arg(long)]
+    system: Option<String>,
+
+    /// Resume a conversation saved as JSON.
+    #[arg(long)]
+    load: Option<PathBuf>,
+
+    /// Save the conversation as JSON on Ctrl+S and on exit.
+    #[arg(long)]
+    save: Option<PathBuf>,
+}
+
+type Term = Terminal<CrosstermBackend<Stdout>>;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(args: Args) -> Result<(), OllamaError> {
+    let host = match args.host.contains("://") {
+        true => args.host,
+        false => format!("http://{}", args.host),
+    };
+    let client = OllamaClient::new(host);
+
+    let mut conversation = match &args.load {
+        Some(path) => Conversation::load(path)?,
+        None => Conversation::new(args.model.clone().unwrap_or_default()),
+    };
+    if let Some(model) = args.model {
+        conversation.model = model;
+    }
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let mut app = App::new(client, conversation, args.save, sender.clone());
+    if let Some(system) = args.system {
+        app.set_system(system);
+    }
+    if app.conversation.model.is_empty() {
+        app.open_model_picker();
+    }
+
+    read_input(sender);
+    let mut terminal = setup_terminal().map_err(io_error)?;
+    let result = app.run(&mut terminal, receiver).await;
+    restore_terminal().map_err(io_error)?;
+    result
+}
+
+/// Forward terminal events to the application from a dedicated thread,
+/// reading them is blocking.
+fn read_input(sender: mpsc::UnboundedSender<Event>) {
+    std::thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            if sender.send(Event::Input(event)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+fn setup_terminal() -> io::Result<Term> {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        hook(info);
+    }));
+
+    terminal::enable_raw_mode()?;
+    io::stdout().execute(EnterAlternateScreen)?;
+    Terminal::new(CrosstermBackend::new(io::stdout()))
+}
+
+fn restore_terminal() -> io::Result<()> {
+    terminal::disable_raw_mode()?;
+    io::stdout().execute(LeaveAlternateScreen)?;
+    Ok(())
+}
+
+fn io_error(e: io::Error) -> OllamaError {
+    OllamaError::IoError(e.to_string())
+}
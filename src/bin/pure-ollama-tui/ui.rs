@@ -0,0 +1,199 @@
+use std::mem;
+
+use pure_ollama::{chat_completion::message::Role, options::Options};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Clear, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::app::{App, Popup};
+
+pub(crate) fn draw(frame: &mut Frame, app: &mut App) {
+    let [conversation, input, status] = Layout::vertical([
+        Constraint::Min(1),
+        Constraint::Length(3),
+        Constraint::Length(1),
+    ])
+    .areas(frame.size());
+
+    draw_conversation(frame, app, conversation);
+    draw_input(frame, app, input);
+    draw_status(frame, app, status);
+
+    let area = centered(frame.size(), 60, 20);
+    match &mut app.popup {
+        Some(Popup::Models { models, state }) => {
+            let items: Vec<ListItem> = match models {
+                Some(models) if models.is_empty() => vec![ListItem::new("No local models.")],
+                Some(models) => models.iter().map(|m| ListItem::new(m.as_str())).collect(),
+                None => vec![ListItem::new("Loading...")],
+            };
+            let list = List::new(items)
+                .block(Block::bordered().title(" Models (Enter to select, Esc to close) "))
+                .highlight_style(Style::new().reversed())
+                .highlight_symbol("> ");
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(list, area, state);
+        }
+        Some(Popup::Options { state, editing }) => {
+            let items: Vec<ListItem> = Options::names()
+                .iter()
+                .map(|name| {
+                    let value = app.conversation.options.get(name);
+                    let value = value.map_or(String::from("-"), |value| value.to_string());
+                    ListItem::new(format!("{name:<16}{value}"))
+                })
+                .collect();
+            let title = match editing {
+                Some(_) => " Options (Enter to apply, empty to unset, Esc to cancel) ",
+                None => " Options (Enter to edit, Del to unset, Esc to close) ",
+            };
+            let list = List::new(items)
+                .block(Block::bordered().title(title))
+                .highlight_style(Style::new().reversed())
+                .highlight_symbol("> ");
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(list, area, state);
+
+            if let Some(value) = editing
+                .as_ref()
+                .filter(|_| area.height > 2 && area.width > 2)
+            {
+                let name = Options::names()[state.selected().unwrap_or_default()];
+                let line = format!("{name} = {value}");
+                let edit = Rect::new(area.x + 1, area.bottom() - 2, area.width - 2, 1);
+                frame.render_widget(Paragraph::new(line.as_str()).reversed(), edit);
+                let cursor = edit.x + (line.chars().count() as u16).min(edit.width - 1);
+                frame.set_cursor(cursor, edit.y);
+            }
+        }
+        None => {}
+    }
+}
+
+fn draw_conversation(frame: &mut Frame, app: &mut App, area: Rect) {
+    let block = Block::bordered().title(format!(" {} ", app.conversation.model));
+    let width = block.inner(area).width.max(1) as usize;
+    let height = block.inner(area).height;
+
+    let mut lines: Vec<Line> = vec![];
+    for message in &app.conversation.messages {
+        push_message(&mut lines, &message.role, &message.content, width);
+    }
+    if let Some(reply) = &app.reply {
+        push_message(
+            &mut lines,
+            &Role::Assistant,
+            &format!("{}▌", reply.content),
+            width,
+        );
+    }
+
+    // Follow the end of the conversation unless scrolled up.
+    let bottom = (lines.len() as u16).saturating_sub(height);
+    if app.follow || app.scroll >= bottom {
+        app.follow = true;
+        app.scroll = bottom;
+    }
+
+    let paragraph = Paragraph::new(lines).block(block).scroll((app.scroll, 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn push_message(lines: &mut Vec<Line>, role: &Role, content: &str, width: usize) {
+    let title = match role {
+        Role::System => "System".magenta().bold(),
+        Role::User => "You".cyan().bold(),
+        Role::Assistant => "Assistant".green().bold(),
+    };
+    lines.push(Line::from(title));
+    for line in content.lines() {
+        lines.extend(wrap(line, width).into_iter().map(Line::from));
+    }
+    lines.push(Line::default());
+}
+
+/// Wrap `text` on word boundaries, splitting words longer than `width`.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut current = String::default();
+    let mut current_width = 0;
+
+    for word in text.split_inclusive(' ') {
+        let word_width = word.chars().count();
+        if current_width + word_width.min(width) > width && current_width > 0 {
+            lines.push(mem::take(&mut current).trim_end().to_string());
+            current_width = 0;
+        }
+        for c in word.chars() {
+            if current_width == width {
+                lines.push(mem::take(&mut current).trim_end().to_string());
+                current_width = 0;
+            }
+            current.push(c);
+            current_width += 1;
+        }
+    }
+    lines.push(current);
+    lines
+}
+
+fn draw_input(frame: &mut Frame, app: &App, area: Rect) {
+    let title = match app.reply {
+        Some(_) => " Message (Esc to stop) ",
+        None => " Message (Enter to send) ",
+    };
+    let block = Block::bordered().title(title);
+    let inner = block.inner(area);
+
+    // Keep the end of a long input visible.
+    let count = app.input.chars().count();
+    let visible = (inner.width as usize).saturating_sub(1);
+    let shown: String = app
+        .input
+        .chars()
+        .skip(count.saturating_sub(visible))
+        .collect();
+    let cursor = inner.x + shown.chars().count() as u16;
+
+    frame.render_widget(Paragraph::new(shown).block(block), area);
+    if app.popup.is_none() {
+        frame.set_cursor(cursor, inner.y);
+    }
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let speed = match (&app.reply, app.tokens_per_second) {
+        (Some(reply), _) => format!(
+            "generating {} tokens, {:.1} tokens/s",
+            reply.chunks,
+            reply.tokens_per_second()
+        ),
+        (None, Some(tokens_per_second)) => format!("{tokens_per_second:.1} tokens/s"),
+        (None, None) => String::from("-- tokens/s"),
+    };
+
+    let mut spans = vec![Span::from(format!(" {speed} "))];
+    if let Some(notice) = &app.notice {
+        spans.push(Span::from(format!("| {notice} ")).bold());
+    }
+    spans.push(Span::from(
+        "| ^P models  ^O options  ^L clear  ^S save  ^C quit",
+    ));
+    frame.render_widget(Paragraph::new(Line::from(spans)).reversed(), area);
+}
+
+/// A rectangle of at most `width` x `height` centered in `area`.
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
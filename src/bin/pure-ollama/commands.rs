@@ -0,0 +1,376 @@
+use std::io::{self, Write};
+
+use pure_ollama::{
+    client::OllamaClient,
+    completion::request::CompletionRequest,
+    errors::OllamaError,
+    model::{
+        copy::CopyModelRequest, delete::DeleteModelRequest, generate_embeddings::EmbeddingsRequest,
+        pull::PullModelRequest, push::PushModelRequest, show_info::ShowModelRequest,
+    },
+    options::Options,
+};
+use serde::Serialize;
+use tokio_stream::StreamExt;
+
+pub(crate) async fn generate(
+    client: &OllamaClient,
+    model: String,
+    prompt: String,
+    system: Option<String>,
+    options: Options,
+    no_stream: bool,
+    json: bool,
+) -> Result<(), OllamaError> {
+    let request = CompletionRequest {
+        model,
+        prompt,
+        system,
+        options,
+        stream: no_stream.then_some(false),
+        ..Default::default()
+    };
+    let response = client.completion(request).await?;
+
+    if no_stream {
+        let response = response.response().await?;
+        return match json {
+            true => print_json(&response),
+            false => {
+                println!("{}", response.response);
+                Ok(())
+            }
+        };
+    }
+
+    let mut stream = response.as_stream().await?;
+    while let Some(item) = stream.next().await {
+        let item = item?;
+        match json {
+            true => print_json_line(&item)?,
+            false => write_flush(&item.response)?,
+        }
+    }
+    if !json {
+        println!();
+    }
+    Ok(())
+}
+
+pub(crate) async fn embed(
+    client: &OllamaClient,
+    model: String,
+    text: String,
+    json: bool,
+) -> Result<(), OllamaError> {
+    let request = EmbeddingsRequest {
+        model,
+        prompt: text,
+        ..Default::default()
+    };
+    let response = client.generate_embeddings(request).await?;
+    match json {
+        true => print_json(&response),
+        false => print_json_line(&response.embedding),
+    }
+}
+
+pub(crate) async fn pull(
+    client: &OllamaClient,
+    name: String,
+    insecure: bool,
+    json: bool,
+) -> Result<(), OllamaError> {
+    let request = PullModelRequest {
+        name,
+        insecure: insecure.then_some(true),
+        stream: None,
+    };
+    let mut stream = client.pull(request).await?.as_stream().await?;
+    let mut progress = Progress::default();
+    while let Some(item) = stream.next().await {
+        let item = item?;
+        if let Some(error) = item.error {
+            progress.finish();
+            return Err(OllamaError::OllamaError(error));
+        }
+        match json {
+            true => print_json_line(&item)?,
+            false => progress.update(&item.status, item.completed, item.total),
+        }
+    }
+    progress.finish();
+    Ok(())
+}
+
+pub(crate) async fn push(
+    client: &OllamaClient,
+    name: String,
+    insecure: bool,
+    json: bool,
+) -> Result<(), OllamaError> {
+    let request = PushModelRequest {
+        name,
+        insecure: insecure.then_some(true),
+        stream: None,
+    };
+    let mut stream = client.push(request).await?.as_stream().await?;
+    let mut progress = Progress::default();
+    while let Some(item) = stream.next().await {
+        let item = item?;
+        if let Some(error) = item.error {
+            progress.finish();
+            return Err(OllamaError::OllamaError(error));
+        }
+        match json {
+            true => print_json_line(&item)?,
+            false => progress.update(&item.status, None, item.total),
+        }
+    }
+    progress.finish();
+    Ok(())
+}
+
+pub(crate) async fn list(client: &OllamaClient, json: bool) -> Result<(), OllamaError> {
+    let response = client.list_local().await?;
+    if json {
+        return print_json(&response);
+    }
+
+    let rows = response
+        .models
+        .iter()
+        .map(|model| {
+            vec![
+                model.name.clone(),
+                short_digest(&model.digest),
+                format_size(model.size),
+                model.modified_at.clone(),
+            ]
+        })
+        .collect();
+    print_table(&["NAME", "ID", "SIZE", "MODIFIED"], rows);
+    Ok(())
+}
+
+pub(crate) async fn ps(client: &OllamaClient, json: bool) -> Result<(), OllamaError> {
+    let response = client.list_running().await?;
+    if json {
+        return print_json(&response);
+    }
+
+    let rows = response
+        .models
+        .iter()
+        .map(|model| {
+            vec![
+                model.name.clone(),
+                short_digest(&model.digest),
+                format_size(model.size),
+                processor(model.size, model.size_vram),
+                model.expires_at.clone(),
+            ]
+        })
+        .collect();
+    print_table(&["NAME", "ID", "SIZE", "PROCESSOR", "UNTIL"], rows);
+    Ok(())
+}
+
+pub(crate) async fn show(
+    client: &OllamaClient,
+    name: String,
+    modelfile: bool,
+    parameters: bool,
+    template: bool,
+    json: bool,
+) -> Result<(), OllamaError> {
+    let request = ShowModelRequest {
+        name,
+        verbose: None,
+    };
+    let response = client.show_info(request).await?;
+    if json {
+        return print_json(&response);
+    }
+
+    if modelfile || parameters || template {
+        for (selected, section) in [
+            (modelfile, &response.modelfile),
+            (parameters, &response.parameters),
+            (template, &response.template),
+        ] {
+            if selected {
+                println!("{}", section.trim_end());
+            }
+        }
+        return Ok(());
+    }
+
+    let details = &response.details;
+    let fields = [
+        ("family", details.family.as_deref()),
+        ("parameters", details.parameter_size.as_deref()),
+        ("quantization", details.quantization_level.as_deref()),
+        ("format", details.format.as_deref()),
+    ];
+    println!("Model");
+    for (label, value) in fields {
+        if let Some(value) = value {
+            println!("  {label:<14}{value}");
+        }
+    }
+    if !response.parameters.trim().is_empty() {
+        println!("\nParameters");
+        for line in response.parameters.lines() {
+            println!("  {}", line.trim());
+        }
+    }
+    if let Some(system) = &response.system {
+        println!("\nSystem\n  {}", system.trim());
+    }
+    if let Some(license) = &response.license {
+        let first = license.lines().next().unwrap_or_default();
+        println!("\nLicense\n  {}", first.trim());
+    }
+    Ok(())
+}
+
+pub(crate) async fn copy(
+    client: &OllamaClient,
+    source: String,
+    destination: String,
+) -> Result<(), OllamaError> {
+    let message = format!("copied '{source}' to '{destination}'");
+    client
+        .copy(CopyModelRequest {
+            source,
+            destination,
+        })
+        .await?;
+    eprintln!("{message}");
+    Ok(())
+}
+
+pub(crate) async fn remove(client: &OllamaClient, names: Vec<String>) -> Result<(), OllamaError> {
+    for name in names {
+        let message = format!("deleted '{name}'");
+        client.delete(DeleteModelRequest { name }).await?;
+        eprintln!("{message}");
+    }
+    Ok(())
+}
+
+/// Write `text` to stdout right away, used to display streamed tokens.
+pub(crate) fn write_flush(text: &str) -> Result<(), OllamaError> {
+    let mut stdout = io::stdout().lock();
+    stdout
+        .write_all(text.as_bytes())
+        .and_then(|_| stdout.flush())
+        .map_err(|e| OllamaError::IoError(e.to_string()))
+}
+
+pub(crate) fn print_json(value: &impl Serialize) -> Result<(), OllamaError> {
+    let json =
+        serde_json::to_string_pretty(value).map_err(|e| OllamaError::ParseError(e.to_string()))?;
+    println!("{json}");
+    Ok(())
+}
+
+pub(crate) fn print_json_line(value: &impl Serialize) -> Result<(), OllamaError> {
+    let json = serde_json::to_string(value).map_err(|e| OllamaError::ParseError(e.to_string()))?;
+    write_flush(&format!("{json}\n"))
+}
+
+fn print_table(headers: &[&str], rows: Vec<Vec<String>>) {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.len()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: Vec<&str>| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        line.join("    ").trim_end().to_string()
+    };
+    println!("{}", format_row(headers.to_vec()));
+    for row in &rows {
+        println!("{}", format_row(row.iter().map(String::as_str).collect()));
+    }
+}
+
+fn short_digest(digest: &str) -> String {
+    let digest = digest.trim_start_matches("sha256:");
+    digest.chars().take(12).collect()
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{bytes} B"),
+        _ => format!("{size:.1} {}", UNITS[unit]),
+    }
+}
+
+fn processor(size: u64, size_vram: u64) -> String {
+    match (size, size_vram) {
+        (_, 0) => String::from("100% CPU"),
+        (size, vram) if vram >= size => String::from("100% GPU"),
+        (size, vram) => {
+            let gpu = vram * 100 / size;
+            format!("{}%/{}% CPU/GPU", 100 - gpu, gpu)
+        }
+    }
+}
+
+/// Progress of a pull or push, rewritten in place on the terminal.
+#[derive(Default)]
+struct Progress {
+    status: String,
+    in_place: bool,
+}
+
+impl Progress {
+    fn update(&mut self, status: &str, completed: Option<u64>, total: Option<u64>) {
+        let line = match (completed, total) {
+            (Some(completed), Some(total)) if total > 0 => format!(
+                "{status} {}% ({}/{})",
+                completed * 100 / total,
+                format_size(completed),
+                format_size(total)
+            ),
+            (None, Some(total)) => format!("{status} ({})", format_size(total)),
+            _ => status.to_string(),
+        };
+
+        let mut stderr = io::stderr().lock();
+        if self.in_place && status == self.status {
+            let _ = write!(stderr, "\r\x1b[K{line}");
+        } else {
+            if self.in_place {
+                let _ = writeln!(stderr);
+            }
+            let _ = write!(stderr, "{line}");
+        }
+        let _ = stderr.flush();
+        self.status = status.to_string();
+        self.in_place = true;
+    }
+
+    fn finish(&mut self) {
+        if self.in_place {
+            eprintln!();
+            self.in_place = false;
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::{Parser, Subcommand};
+use pure_ollama::{
+    client::{OllamaClient, DEFAULT_HOST},
+    errors::OllamaError,
+    options::Options,
+};
+
+mod commands;
+mod repl;
+
+/// Chat, generate and manage models with an Ollama server.
+#[derive(Debug, Parser)]
+#[command(name = "pure-ollama", version)]
+struct Cli {
+    /// Base URL of the Ollama server.
+    #[arg(long, global = true, env = "OLLAMA_HOST", default_value = DEFAULT_HOST)]
+    host: String,
+
+    /// Print JSON instead of text, one object per line for streamed output.
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Chat with a model in an interactive session.
+    Chat {
+        model: String,
+
+        /// System message of the conversation.
+        #[arg(long)]
+        system: Option<String>,
+
+        /// Model parameter, e.g. `-o temperature=0`.
+        #[arg(short = 'o', long = "option", value_name = "KEY=VALUE")]
+        options: Vec<String>,
+
+        /// Resume a conversation saved with `/save`, with `model`.
+        #[arg(long)]
+        load: Option<PathBuf>,
+    },
+
+    /// Generate a response for a prompt, read from stdin when omitted.
+    Generate {
+        model: String,
+
+        prompt: Option<String>,
+
+        /// System message overriding the one of the Modelfile.
+        #[arg(long)]
+        system: Option<String>,
+
+        /// Model parameter, e.g. `-o num_predict=64`.
+        #[arg(short = 'o', long = "option", value_name = "KEY=VALUE")]
+        options: Vec<String>,
+
+        /// Wait for the whole response instead of streaming it.
+        #[arg(long)]
+        no_stream: bool,
+    },
+
+    /// Generate embeddings for a text, read from stdin when omitted.
+    Embed { model: String, text: Option<String> },
+
+    /// Pull a model from the library.
+    Pull {
+        name: String,
+
+        #[arg(long)]
+        insecure: bool,
+    },
+
+    /// Push a model to the library.
+    Push {
+        name: String,
+
+        #[arg(long)]
+        insecure: bool,
+    },
+
+    /// List local models.
+    List,
+
+    /// List running models.
+    Ps,
+
+    /// Show information about a model.
+    Show {
+        name: String,
+
+        /// Only print the Modelfile.
+        #[arg(long)]
+        modelfile: bool,
+
+        /// Only print the parameters.
+        #[arg(long)]
+        parameters: bool,
+
+        /// Only print the template.
+        #[arg(long)]
+        template: bool,
+    },
+
+    /// Copy a model.
+    Cp { source: String, destination: String },
+
+    /// Remove models.
+    Rm {
+        #[arg(required = true)]
+        names: Vec<String>,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), OllamaError> {
+    let client = OllamaClient::new(normalize_host(&cli.host));
+    let json = cli.json;
+
+    match cli.command {
+        Command::Chat {
+            model,
+            system,
+            options,
+            load,
+        } => repl::run(&client, model, system, parse_options(&options)?, load, json).await,
+        Command::Generate {
+            model,
+            prompt,
+            system,
+            options,
+            no_stream,
+        } => {
+            let prompt = prompt_or_stdin(prompt)?;
+            let options = parse_options(&options)?;
+            commands::generate(&client, model, prompt, system, options, no_stream, json).await
+        }
+        Command::Embed { model, text } => {
+            commands::embed(&client, model, prompt_or_stdin(text)?, json).await
+        }
+        Command::Pull { name, insecure } => commands::pull(&client, name, insecure, json).await,
+        Command::Push { name, insecure } => commands::push(&client, name, insecure, json).await,
+        Command::List => commands::list(&client, json).await,
+        Command::Ps => commands::ps(&client, json).await,
+        Command::Show {
+            name,
+            modelfile,
+            parameters,
+            template,
+        } => commands::show(&client, name, modelfile, parameters, template, json).await,
+        Command::Cp {
+            source,
+            destination,
+        } => commands::copy(&client, source, destination).await,
+        Command::Rm { names } => commands::remove(&client, names).await,
+    }
+}
+
+/// Accept `OLLAMA_HOST` values without a scheme, such as `0.0.0.0:11434`.
+fn normalize_host(host: &str) -> String {
+    match host.contains("://") {
+        true => host.to_string(),
+        false => format!("http://{host}"),
+    }
+}
+
+/// Parse `KEY=VALUE` pairs into model options.
+pub(crate) fn parse_options(pairs: &[String]) -> Result<Options, OllamaError> {
+    let mut options = Options::default();
+    for pair in pairs {
+        let (name, value) = pair.split_once('=').ok_or_else(|| {
+            OllamaError::InvalidParameter(format!("expected KEY=VALUE, got: {pair}"))
+        })?;
+        options.set(name.trim(), value.trim())?;
+    }
+    Ok(options)
+}
+
+fn prompt_or_stdin(prompt: Option<String>) -> Result<String, OllamaError> {
+    match prompt {
+        Some(prompt) => Ok(prompt),
+        None => std::io::read_to_string(std::io::stdin())
+            .map_err(|e| OllamaError::IoError(e.to_string())),
+    }
+}
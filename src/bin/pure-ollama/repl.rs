@@ -0,0 +1,313 @@
+use std::path::{Path, PathBuf};
+
+use pure_ollama::{
+    chat_completion::{
+        message::{Message, Role},
+        response::ChatResponse,
+    },
+    client::OllamaClient,
+    control::{CancellationToken, RequestControl, RequestControlBuilder},
+    conversation::Conversation,
+    errors::OllamaError,
+    options::Options,
+};
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+
+use crate::commands::{print_json_line, write_flush};
+
+const HELP: &str = "\
+Available commands:
+  /system <text>         Set the system message
+  /options               Show the model options
+  /options <key=value>   Set model options, e.g. /options temperature=0.2
+  /options reset         Reset the model options
+  /model <name>          Switch to another model
+  /history               Show the messages of the conversation
+  /clear                 Forget the messages, keeping the system message
+  /save <path>           Save the conversation, as JSONL if the path ends with .jsonl
+  /load <path>           Load a saved conversation
+  /help                  Show this help
+  /bye                   Exit
+
+Press Ctrl+C to interrupt a reply, and Ctrl+C or Ctrl+D at the prompt to exit.";
+
+enum Flow {
+    Continue,
+    Exit,
+}
+
+/// Run an interactive chat session reading prompts from stdin.
+pub(crate) async fn run(
+    client: &OllamaClient,
+    model: String,
+    system: Option<String>,
+    options: Options,
+    load: Option<PathBuf>,
+    json: bool,
+) -> Result<(), OllamaError> {
+    let mut conversation = match load {
+        Some(path) => load_conversation(&path)?,
+        None => Conversation::new(model.clone()),
+    };
+    conversation.model = model;
+    if !options.is_default() {
+        conversation.options = options;
+    }
+    if let Some(system) = system {
+        set_system(&mut conversation, system);
+    }
+
+    let mut lines = read_lines();
+    if !json {
+        eprintln!("Chatting with {}. Type /help for help.", conversation.model);
+    }
+
+    loop {
+        if !json {
+            write_flush(">>> ")?;
+        }
+        // Listening to Ctrl+C replaces its default handler for the rest of the
+        // process, so exit explicitly when it is pressed at the prompt.
+        let line = tokio::select! {
+            line = lines.recv() => line,
+            _ = tokio::signal::ctrl_c() => break,
+        };
+        let line = match line {
+            Some(Ok(line)) => line,
+            None => break,
+            Some(Err(e)) => return Err(OllamaError::IoError(e.to_string())),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(command) = line.strip_prefix('/') {
+            match handle_command(&mut conversation, command) {
+                Ok(Flow::Continue) => continue,
+                Ok(Flow::Exit) => break,
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    continue;
+                }
+            }
+        }
+
+        conversation.push_user(line);
+        match reply(client, &conversation, json).await {
+            Ok(response) => conversation.record_chat(&response),
+            Err(e) => {
+                // Drop the unanswered prompt so that it can be sent again.
+                conversation.messages.pop();
+                eprintln!("Error: {e}");
+            }
+        }
+    }
+
+    if !json {
+        eprintln!();
+    }
+    Ok(())
+}
+
+/// Forward the lines of stdin from a dedicated thread: a blocking read can't be
+/// cancelled, and would keep the runtime from shutting down on exit.
+fn read_lines() -> mpsc::UnboundedReceiver<std::io::Result<String>> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lines() {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Stream the reply to the conversation, interrupted by Ctrl+C.
+async fn reply(
+    client: &OllamaClient,
+    conversation: &Conversation,
+    json: bool,
+) -> Result<ChatResponse, OllamaError> {
+    let token = CancellationToken::new();
+    let interrupt = tokio::spawn({
+        let token = token.clone();
+        async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                token.cancel();
+            }
+        }
+    });
+    let control = RequestControlBuilder::default()
+        .cancellation(token)
+        .build()
+        .unwrap();
+
+    let result = stream_reply(client, conversation, control, json).await;
+    interrupt.abort();
+    result
+}
+
+async fn stream_reply(
+    client: &OllamaClient,
+    conversation: &Conversation,
+    control: RequestControl,
+    json: bool,
+) -> Result<ChatResponse, OllamaError> {
+    let mut stream = client
+        .chat_with_control(conversation.chat_request(), control)
+        .await?
+        .as_stream()
+        .await?;
+
+    let mut content = String::default();
+    let mut last = ChatResponse::default();
+    while let Some(item) = stream.next().await {
+        let item = match item {
+            Ok(item) => item,
+            Err(e) => {
+                if !json && !content.is_empty() {
+                    println!();
+                }
+                return Err(e);
+            }
+        };
+        let part = item.message.as_ref().map(|m| m.content.as_str());
+        let part = part.unwrap_or_default();
+        match json {
+            true => print_json_line(&item)?,
+            false => write_flush(part)?,
+        }
+        content += part;
+        last = item;
+    }
+    if !json {
+        println!("\n");
+    }
+
+    last.message = Some(Message {
+        role: Role::Assistant,
+        content,
+        images: None,
+    });
+    Ok(last)
+}
+
+fn handle_command(conversation: &mut Conversation, command: &str) -> Result<Flow, OllamaError> {
+    let (name, argument) = match command.split_once(char::is_whitespace) {
+        Some((name, argument)) => (name, argument.trim()),
+        None => (command, ""),
+    };
+
+    match (name, argument) {
+        ("bye" | "exit" | "quit", _) => return Ok(Flow::Exit),
+        ("help" | "?", _) => eprintln!("{HELP}"),
+        ("system", "") => match system_message(conversation) {
+            Some(system) => eprintln!("{system}"),
+            None => eprintln!("No system message."),
+        },
+        ("system", system) => {
+            set_system(conversation, system.to_string());
+            eprintln!("Set system message.");
+        }
+        ("options", "") => {
+            let options = serde_json::to_string_pretty(&conversation.options)
+                .map_err(|e| OllamaError::ParseError(e.to_string()))?;
+            eprintln!("{options}");
+        }
+        ("options", "reset") => {
+            conversation.options = Options::default();
+            eprintln!("Reset options.");
+        }
+        ("options", pairs) => {
+            let pairs: Vec<String> = pairs.split_whitespace().map(String::from).collect();
+            let mut options = conversation.options.clone();
+            for pair in &pairs {
+                let (name, value) = pair.split_once('=').ok_or_else(|| {
+                    OllamaError::InvalidParameter(format!("expected KEY=VALUE, got: {pair}"))
+                })?;
+                options.set(name, value)?;
+            }
+            conversation.options = options;
+            eprintln!("Set options.");
+        }
+        ("model", "") => eprintln!("{}", conversation.model),
+        ("model", model) => {
+            conversation.model = model.to_string();
+            eprintln!("Switched to {model}.");
+        }
+        ("history", _) => {
+            for message in &conversation.messages {
+                eprintln!("{}: {}", message.role.to_string(), message.content);
+            }
+        }
+        ("clear", _) => {
+            conversation
+                .messages
+                .retain(|message| message.role == Role::System);
+            eprintln!("Cleared the conversation.");
+        }
+        ("save", "") | ("load", "") => {
+            return Err(OllamaError::InvalidParameter(format!(
+                "usage: /{name} <path>"
+            )))
+        }
+        ("save", path) => {
+            match is_jsonl(Path::new(path)) {
+                true => conversation.save_jsonl(path)?,
+                false => conversation.save(path)?,
+            }
+            eprintln!("Saved the conversation to {path}.");
+        }
+        ("load", path) => {
+            *conversation = load_conversation(Path::new(path))?;
+            eprintln!(
+                "Loaded {} messages with {}.",
+                conversation.messages.len(),
+                conversation.model
+            );
+        }
+        _ => {
+            return Err(OllamaError::InvalidParameter(format!(
+                "unknown command /{name}, type /help for help"
+            )))
+        }
+    }
+
+    Ok(Flow::Continue)
+}
+
+fn system_message(conversation: &Conversation) -> Option<&str> {
+    conversation
+        .messages
+        .first()
+        .filter(|message| message.role == Role::System)
+        .map(|message| message.content.as_str())
+}
+
+/// Replace the leading system message of the conversation.
+fn set_system(conversation: &mut Conversation, system: String) {
+    let message = Message {
+        role: Role::System,
+        content: system,
+        images: None,
+    };
+    match system_message(conversation) {
+        Some(_) => conversation.messages[0] = message,
+        None => conversation.messages.insert(0, message),
+    }
+}
+
+fn is_jsonl(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "jsonl")
+}
+
+fn load_conversation(path: &Path) -> Result<Conversation, OllamaError> {
+    match is_jsonl(path) {
+        true => Conversation::load_jsonl(path),
+        false => Conversation::load(path),
+    }
+}
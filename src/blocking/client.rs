@@ -0,0 +1,214 @@
+use std::{sync::Arc, time::Duration};
+
+use serde::de::DeserializeOwned;
+use tokio::runtime::Runtime;
+use tokio_stream::StreamExt;
+
+use crate::{
+    chat_completion::{request::ChatCompletionRequest, response::ChatResponse},
+    client::{self, VersionResponse},
+    completion::{request::CompletionRequest, response::CompletionResponse},
+    control::RequestControl,
+    errors::OllamaError,
+    model::{
+        copy::CopyModelRequest,
+        create::{CreateModelRequest, CreateModelResponse},
+        delete::DeleteModelRequest,
+        generate_embeddings::{EmbeddingsRequest, EmbeddingsResponse},
+        list_local::ListLocalModelsResponse,
+        list_running::ListRunningModelsResponse,
+        pull::{PullModelRequest, PullModelResponse},
+        push::{PushModelRequest, PushModelResponse},
+        show_info::{ShowModelRequest, ShowModelResponse},
+    },
+    response::{OllamaResponse, OllamaStream, StreamHandler},
+};
+
+/// A synchronous client mirroring [`crate::client::OllamaClient`]. Each client owns a
+/// single-threaded runtime driving the async client, so it must not be used from
+/// within an async context.
+#[derive(Debug, Clone)]
+pub struct OllamaClient {
+    inner: client::OllamaClient,
+    runtime: Arc<Runtime>,
+}
+
+impl OllamaClient {
+    pub fn new(host: impl Into<String>) -> Result<Self, OllamaError> {
+        Self::from_async(client::OllamaClient::new(host))
+    }
+
+    /// Wrap an async client, keeping its retry policy and other settings.
+    pub fn from_async(inner: client::OllamaClient) -> Result<Self, OllamaError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| OllamaError::IoError(e.to_string()))?;
+
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    #[inline]
+    pub fn host(&self) -> &str {
+        self.inner.host()
+    }
+
+    pub fn chat(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<BlockingResponse<ChatResponse>, OllamaError> {
+        self.chat_with_control(request, RequestControl::default())
+    }
+
+    pub fn chat_with_control(
+        &self,
+        request: ChatCompletionRequest,
+        control: RequestControl,
+    ) -> Result<BlockingResponse<ChatResponse>, OllamaError> {
+        let response = self
+            .runtime
+            .block_on(self.inner.chat_with_control(request, control))?;
+        Ok(self.wrap(response))
+    }
+
+    pub fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<BlockingResponse<CompletionResponse>, OllamaError> {
+        self.completion_with_control(request, RequestControl::default())
+    }
+
+    pub fn completion_with_control(
+        &self,
+        request: CompletionRequest,
+        control: RequestControl,
+    ) -> Result<BlockingResponse<CompletionResponse>, OllamaError> {
+        let response = self
+            .runtime
+            .block_on(self.inner.completion_with_control(request, control))?;
+        Ok(self.wrap(response))
+    }
+
+    pub fn generate_embeddings(
+        &self,
+        request: EmbeddingsRequest,
+    ) -> Result<EmbeddingsResponse, OllamaError> {
+        self.runtime
+            .block_on(self.inner.generate_embeddings(request))
+    }
+
+    pub fn create(
+        &self,
+        request: CreateModelRequest,
+    ) -> Result<BlockingResponse<CreateModelResponse>, OllamaError> {
+        let response = self.runtime.block_on(self.inner.create(request))?;
+        Ok(self.wrap(response))
+    }
+
+    pub fn list_local(&self) -> Result<ListLocalModelsResponse, OllamaError> {
+        self.runtime.block_on(self.inner.list_local())
+    }
+
+    pub fn list_running(&self) -> Result<ListRunningModelsResponse, OllamaError> {
+        self.runtime.block_on(self.inner.list_running())
+    }
+
+    pub fn show_info(&self, request: ShowModelRequest) -> Result<ShowModelResponse, OllamaError> {
+        self.runtime.block_on(self.inner.show_info(request))
+    }
+
+    pub fn copy(&self, request: CopyModelRequest) -> Result<(), OllamaError> {
+        self.runtime.block_on(self.inner.copy(request))
+    }
+
+    pub fn delete(&self, request: DeleteModelRequest) -> Result<(), OllamaError> {
+        self.runtime.block_on(self.inner.delete(request))
+    }
+
+    pub fn pull(
+        &self,
+        request: PullModelRequest,
+    ) -> Result<BlockingResponse<PullModelResponse>, OllamaError> {
+        let response = self.runtime.block_on(self.inner.pull(request))?;
+        Ok(self.wrap(response))
+    }
+
+    pub fn push(
+        &self,
+        request: PushModelRequest,
+    ) -> Result<BlockingResponse<PushModelResponse>, OllamaError> {
+        let response = self.runtime.block_on(self.inner.push(request))?;
+        Ok(self.wrap(response))
+    }
+
+    pub fn version(&self) -> Result<VersionResponse, OllamaError> {
+        self.runtime.block_on(self.inner.version())
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.runtime.block_on(self.inner.is_alive())
+    }
+
+    pub fn wait_until_ready(&self, timeout: Duration) -> Result<(), OllamaError> {
+        self.runtime.block_on(self.inner.wait_until_ready(timeout))
+    }
+
+    fn wrap<T>(&self, response: OllamaResponse<T>) -> BlockingResponse<T> {
+        BlockingResponse {
+            response,
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// The synchronous counterpart of [`OllamaResponse`].
+pub struct BlockingResponse<T> {
+    response: OllamaResponse<T>,
+    runtime: Arc<Runtime>,
+}
+
+impl<T> BlockingResponse<T>
+where
+    T: DeserializeOwned,
+{
+    /// Read a single JSON object, for requests sent with "stream": false.
+    pub fn response(self) -> Result<T, OllamaError> {
+        self.runtime.block_on(self.response.response())
+    }
+}
+
+impl<T> BlockingResponse<T>
+where
+    T: StreamHandler + DeserializeOwned + Send + 'static,
+{
+    /// Iterate over the streamed chunks.
+    pub fn as_stream(self) -> Result<BlockingStream<T>, OllamaError> {
+        let stream = self.runtime.block_on(self.response.as_stream())?;
+        Ok(BlockingStream {
+            stream,
+            runtime: self.runtime,
+        })
+    }
+
+    /// Read the whole stream and merge it into a single response.
+    pub fn as_response(self) -> Result<T, OllamaError> {
+        self.runtime.block_on(self.response.as_response())
+    }
+}
+
+/// An iterator over the chunks of a streamed response.
+pub struct BlockingStream<T> {
+    stream: OllamaStream<T>,
+    runtime: Arc<Runtime>,
+}
+
+impl<T> Iterator for BlockingStream<T> {
+    type Item = Result<T, OllamaError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
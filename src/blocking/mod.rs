@@ -0,0 +1,8 @@
+//! A synchronous client, available with the `blocking` feature.
+
+pub mod client;
+
+// test module
+mod test_blocking;
+
+pub use client::{BlockingResponse, BlockingStream, OllamaClient};
@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        blocking::OllamaClient,
+        chat_completion::request::ChatCompletionRequestBuilder,
+        completion::request::CompletionRequestBuilder,
+        testing::{MockOllama, MockResponse},
+    };
+
+    #[test]
+    fn test_blocking_client() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mock = runtime.block_on(MockOllama::start());
+        mock.respond(
+            "/api/generate",
+            MockResponse::completion_stream("llama3", &["a", "b", "c"], vec![1]),
+        );
+
+        let client = OllamaClient::new(mock.url()).unwrap();
+        client.wait_until_ready(Duration::from_secs(1)).unwrap();
+
+        let request = CompletionRequestBuilder::default()
+            .model("llama3")
+            .prompt("hello")
+            .build()
+            .unwrap();
+        let chunks: Vec<String> = client
+            .completion(request)
+            .unwrap()
+            .as_stream()
+            .unwrap()
+            .map(|item| item.unwrap().response)
+            .collect();
+        assert_eq!(chunks, vec!["a", "b", "c", ""]);
+
+        let request = ChatCompletionRequestBuilder::default()
+            .model("llama3")
+            .messages(vec![])
+            .build()
+            .unwrap();
+        let response = client.chat(request).unwrap().as_response().unwrap();
+        assert_eq!(response.message.unwrap().content, "Hello from mock");
+
+        assert!(client.list_local().unwrap().models.is_empty());
+    }
+}
@@ -0,0 +1,262 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_stream::stream;
+use async_trait::async_trait;
+use bytes::Bytes;
+use tokio_stream::StreamExt;
+
+use super::store::CacheStore;
+use crate::{
+    api::{request_key, OllamaApi},
+    chat_completion::{request::ChatCompletionRequest, response::ChatResponse},
+    completion::{request::CompletionRequest, response::CompletionResponse},
+    control::RequestControl,
+    errors::OllamaError,
+    model::{
+        copy::CopyModelRequest,
+        create::{CreateModelRequest, CreateModelResponse},
+        delete::DeleteModelRequest,
+        generate_embeddings::{EmbeddingsRequest, EmbeddingsResponse},
+        list_local::ListLocalModelsResponse,
+        list_running::ListRunningModelsResponse,
+        pull::{PullModelRequest, PullModelResponse},
+        push::{PushModelRequest, PushModelResponse},
+        show_info::{ShowModelRequest, ShowModelResponse},
+    },
+    options::Options,
+    response::{split_lines, OllamaResponse},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl Counters {
+    pub(crate) fn count(&self, hit: bool) {
+        let counter = match hit {
+            true => &self.hits,
+            false => &self.misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Serves repeated deterministic requests from a [`CacheStore`] instead of the
+/// server.
+///
+/// Chat and completion requests are cached when they set a `seed` and a
+/// `temperature` of 0, so that the server would generate the same response
+/// again; embeddings requests are always cached. Requests are keyed by their
+/// path and canonical serialized body. Cached responses are replayed chunk by
+/// chunk, streamed responses included. A response is stored once its body has
+/// been read to the end without error. Other calls are forwarded as is.
+#[derive(Clone)]
+pub struct ResponseCache<A> {
+    api: A,
+    store: Arc<dyn CacheStore>,
+    ttl: Option<Duration>,
+    counters: Arc<Counters>,
+}
+
+impl<A: OllamaApi> ResponseCache<A> {
+    pub fn new(api: A, store: impl CacheStore + 'static) -> Self {
+        Self {
+            api,
+            store: Arc::new(store),
+            ttl: None,
+            counters: Arc::default(),
+        }
+    }
+
+    /// Expire the responses stored from now on after `ttl`.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// The number of cacheable requests served from the store, and sent to the server.
+    pub fn stats(&self) -> CacheStats {
+        self.counters.stats()
+    }
+
+    fn lookup(&self, key: &str) -> Option<Vec<String>> {
+        let chunks = self.store.get(key);
+        self.counters.count(chunks.is_some());
+        chunks
+    }
+
+    /// Pass the body of `response` through while storing its chunks under `key`
+    /// once it has been read to the end.
+    fn tee<T>(&self, key: String, response: OllamaResponse<T>) -> OllamaResponse<T> {
+        let store = self.store.clone();
+        let ttl = self.ttl;
+        record(response, move |chunks| {
+            // Failing to store the response must not break the call.
+            let _ = store.put(&key, chunks, ttl);
+        })
+    }
+}
+
+/// Pass the body of `response` through, handing its lines to `complete` once it
+/// has been read to the end without error. The body is re-framed on new lines so
+/// that no character is split between two stored chunks.
+pub(crate) fn record<T, F>(response: OllamaResponse<T>, complete: F) -> OllamaResponse<T>
+where
+    F: FnOnce(Vec<String>) + Send + Sync + 'static,
+{
+    response.map_body(move |input| {
+        let mut input = Box::pin(split_lines(input));
+        stream! {
+            let mut lines = vec![];
+            while let Some(item) = input.next().await {
+                match item {
+                    Ok(line) => {
+                        lines.push(String::from_utf8_lossy(&line).into_owned());
+                        yield Ok(line);
+                    }
+                    Err(e) => {
+                        yield Err(e);
+                        return;
+                    }
+                }
+            }
+            complete(lines);
+        }
+    })
+}
+
+/// True if the server would generate the same response to the same request.
+fn is_deterministic(options: &Options) -> bool {
+    options.seed.is_some() && options.temperature == Some(0.0)
+}
+
+pub(crate) fn replay<T>(chunks: Vec<String>) -> OllamaResponse<T> {
+    OllamaResponse::from_chunks(chunks.into_iter().map(Bytes::from).collect())
+}
+
+#[async_trait]
+impl<A: OllamaApi> OllamaApi for ResponseCache<A> {
+    async fn chat_with_control(
+        &self,
+        request: ChatCompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<ChatResponse>, OllamaError> {
+        if !is_deterministic(&request.options) {
+            return self.api.chat_with_control(request, control).await;
+        }
+        let key = request_key("/api/chat", &request)?;
+        if let Some(chunks) = self.lookup(&key) {
+            return Ok(replay(chunks));
+        }
+        let response = self.api.chat_with_control(request, control).await?;
+        Ok(self.tee(key, response))
+    }
+
+    async fn completion_with_control(
+        &self,
+        request: CompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<CompletionResponse>, OllamaError> {
+        if !is_deterministic(&request.options) {
+            return self.api.completion_with_control(request, control).await;
+        }
+        let key = request_key("/api/generate", &request)?;
+        if let Some(chunks) = self.lookup(&key) {
+            return Ok(replay(chunks));
+        }
+        let response = self.api.completion_with_control(request, control).await?;
+        Ok(self.tee(key, response))
+    }
+
+    async fn generate_embeddings(
+        &self,
+        request: EmbeddingsRequest,
+    ) -> Result<EmbeddingsResponse, OllamaError> {
+        let key = request_key("/api/embeddings", &request)?;
+        let cached = self
+            .lookup(&key)
+            .and_then(|chunks| serde_json::from_str(&chunks.concat()).ok());
+        if let Some(response) = cached {
+            return Ok(response);
+        }
+
+        let response = self.api.generate_embeddings(request).await?;
+        if let Ok(body) = serde_json::to_string(&response) {
+            let _ = self.store.put(&key, vec![body], self.ttl);
+        }
+        Ok(response)
+    }
+
+    async fn create(
+        &self,
+        request: CreateModelRequest,
+    ) -> Result<OllamaResponse<CreateModelResponse>, OllamaError> {
+        self.api.create(request).await
+    }
+
+    async fn list_local(&self) -> Result<ListLocalModelsResponse, OllamaError> {
+        self.api.list_local().await
+    }
+
+    async fn list_running(&self) -> Result<ListRunningModelsResponse, OllamaError> {
+        self.api.list_running().await
+    }
+
+    async fn show_info(&self, request: ShowModelRequest) -> Result<ShowModelResponse, OllamaError> {
+        self.api.show_info(request).await
+    }
+
+    async fn copy(&self, request: CopyModelRequest) -> Result<(), OllamaError> {
+        self.api.copy(request).await
+    }
+
+    async fn delete(&self, request: DeleteModelRequest) -> Result<(), OllamaError> {
+        self.api.delete(request).await
+    }
+
+    async fn pull(
+        &self,
+        request: PullModelRequest,
+    ) -> Result<OllamaResponse<PullModelResponse>, OllamaError> {
+        self.api.pull(request).await
+    }
+
+    async fn push(
+        &self,
+        request: PushModelRequest,
+    ) -> Result<OllamaResponse<PushModelResponse>, OllamaError> {
+        self.api.push(request).await
+    }
+}
+
+impl<A: fmt::Debug> fmt::Debug for ResponseCache<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("api", &self.api)
+            .field("ttl", &self.ttl)
+            .field("stats", &self.counters)
+            .finish()
+    }
+}
@@ -0,0 +1,11 @@
+pub mod cache;
+pub mod semantic;
+pub mod store;
+
+// test module
+mod test_cache;
+mod test_semantic;
+
+pub use cache::{CacheStats, ResponseCache};
+pub use semantic::SemanticCache;
+pub use store::{CacheStore, DiskCache, MemoryCache};
@@ -0,0 +1,344 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::cache::{record, replay, CacheStats, Counters};
+use crate::{
+    api::OllamaApi,
+    chat_completion::{message::Role, request::ChatCompletionRequest, response::ChatResponse},
+    completion::{request::CompletionRequest, response::CompletionResponse},
+    control::RequestControl,
+    errors::OllamaError,
+    model::{
+        canonical_name,
+        copy::CopyModelRequest,
+        create::{CreateModelRequest, CreateModelResponse},
+        delete::DeleteModelRequest,
+        generate_embeddings::{EmbeddingsRequest, EmbeddingsResponse},
+        list_local::ListLocalModelsResponse,
+        list_running::ListRunningModelsResponse,
+        pull::{PullModelRequest, PullModelResponse},
+        push::{PushModelRequest, PushModelResponse},
+        show_info::{ShowModelRequest, ShowModelResponse},
+    },
+    response::OllamaResponse,
+};
+
+/// Default minimum cosine similarity between two prompts for one to be answered
+/// with the response to the other.
+pub const DEFAULT_SIMILARITY_THRESHOLD: f64 = 0.95;
+
+/// Default maximum number of answered prompts kept.
+pub const DEFAULT_MAX_ENTRIES: usize = 1024;
+
+#[derive(Debug)]
+struct Entry {
+    scope: String,
+    embedding: Vec<f64>,
+    chunks: Vec<String>,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    entries: Vec<Entry>,
+    tick: u64,
+}
+
+/// A prompt to look up: the text to embed, and what must be identical for two
+/// prompts to share a response.
+struct Prompt {
+    scope: String,
+    text: String,
+}
+
+/// Answers chat and completion requests with the response to a previously
+/// answered, similar enough prompt instead of generating a new one.
+///
+/// Prompts are embedded with `embedding_model` and compared by cosine
+/// similarity. Only prompts sent to the same model, with the same system prompt,
+/// template, format and streaming mode, are compared. The prompt of a chat is its
+/// last user message, compared only with chats whose other messages are identical.
+/// Requests with images or a completion context aren't cached, nor are chats
+/// without a user message or requests whose prompt couldn't be embedded. A response is stored once its body has been read
+/// to the end without error; the least recently used one is evicted beyond
+/// `max_entries`. Other calls are forwarded as is.
+#[derive(Clone)]
+pub struct SemanticCache<A> {
+    api: A,
+    embedding_model: String,
+    threshold: f64,
+    max_entries: usize,
+    entries: Arc<Mutex<Entries>>,
+    counters: Arc<Counters>,
+}
+
+impl<A: OllamaApi> SemanticCache<A> {
+    pub fn new(api: A, embedding_model: impl Into<String>) -> Self {
+        Self {
+            api,
+            embedding_model: embedding_model.into(),
+            threshold: DEFAULT_SIMILARITY_THRESHOLD,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            entries: Arc::default(),
+            counters: Arc::default(),
+        }
+    }
+
+    /// Answer prompts whose cosine similarity to an answered one is at least `threshold`.
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Keep at most `max_entries` answered prompts.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// The number of cacheable requests answered from the cache, and sent to the server.
+    pub fn stats(&self) -> CacheStats {
+        self.counters.stats()
+    }
+
+    /// The number of answered prompts kept.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().entries.clear();
+    }
+
+    async fn embed(&self, text: String) -> Option<Vec<f64>> {
+        let request = EmbeddingsRequest {
+            model: self.embedding_model.clone(),
+            prompt: text,
+            ..Default::default()
+        };
+        let response = self.api.generate_embeddings(request).await.ok()?;
+        Some(response.embedding).filter(|embedding| !embedding.is_empty())
+    }
+
+    /// The chunks of the response to the most similar prompt in `scope`, if
+    /// similar enough.
+    fn lookup(&self, scope: &str, embedding: &[f64]) -> Option<Vec<String>> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.tick += 1;
+        let tick = entries.tick;
+
+        let best = entries
+            .entries
+            .iter_mut()
+            .filter(|entry| entry.scope == scope)
+            .map(|entry| (cosine_similarity(&entry.embedding, embedding), entry))
+            .filter(|(similarity, _)| *similarity >= self.threshold)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b));
+        let chunks = best.map(|(_, entry)| {
+            entry.last_used = tick;
+            entry.chunks.clone()
+        });
+        self.counters.count(chunks.is_some());
+        chunks
+    }
+
+    /// Answer `prompt` from the cache, or with `call` whose response is then stored.
+    async fn answer<T, F, Fut>(
+        &self,
+        prompt: Option<Prompt>,
+        call: F,
+    ) -> Result<OllamaResponse<T>, OllamaError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<OllamaResponse<T>, OllamaError>>,
+    {
+        let Some(Prompt { scope, text }) = prompt else {
+            return call().await;
+        };
+        let Some(embedding) = self.embed(text).await else {
+            return call().await;
+        };
+        if let Some(chunks) = self.lookup(&scope, &embedding) {
+            return Ok(replay(chunks));
+        }
+
+        let response = call().await?;
+        let entries = self.entries.clone();
+        let max_entries = self.max_entries;
+        Ok(record(response, move |chunks| {
+            let mut entries = entries.lock().unwrap();
+            entries.tick += 1;
+            let last_used = entries.tick;
+            entries.entries.push(Entry {
+                scope,
+                embedding,
+                chunks,
+                last_used,
+            });
+            if entries.entries.len() > max_entries {
+                let oldest = (0..entries.entries.len())
+                    .min_by_key(|&i| entries.entries[i].last_used)
+                    .unwrap();
+                entries.entries.swap_remove(oldest);
+            }
+        }))
+    }
+}
+
+fn chat_prompt(request: &ChatCompletionRequest) -> Option<Prompt> {
+    if request.messages.iter().any(|m| m.images.is_some()) {
+        return None;
+    }
+    let last = request
+        .messages
+        .iter()
+        .rposition(|message| message.role == Role::User)?;
+    // The rest of the conversation, roles included, must match exactly.
+    let history: Vec<_> = request
+        .messages
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != last)
+        .map(|(_, message)| json!({ "role": message.role, "content": message.content }))
+        .collect();
+
+    let scope = json!({
+        "path": "/api/chat",
+        "model": canonical_name(&request.model),
+        "history": history,
+        "format": request.format,
+        "stream": request.stream,
+    });
+    Some(Prompt {
+        scope: scope.to_string(),
+        text: request.messages[last].content.clone(),
+    })
+}
+
+fn completion_prompt(request: &CompletionRequest) -> Option<Prompt> {
+    if !request.images.is_empty() || request.context.is_some() {
+        return None;
+    }
+    let scope = json!({
+        "path": "/api/generate",
+        "model": canonical_name(&request.model),
+        "system": request.system,
+        "template": request.template,
+        "raw": request.raw,
+        "format": request.format,
+        "stream": request.stream,
+    });
+    Some(Prompt {
+        scope: scope.to_string(),
+        text: request.prompt.clone(),
+    })
+}
+
+/// The cosine similarity of `a` and `b`, 0 if their lengths differ.
+fn cosine_similarity(a: &[f64], b: &[f64]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f64]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        return 0.0;
+    }
+    dot / norms
+}
+
+#[async_trait]
+impl<A: OllamaApi> OllamaApi for SemanticCache<A> {
+    async fn chat_with_control(
+        &self,
+        request: ChatCompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<ChatResponse>, OllamaError> {
+        let prompt = chat_prompt(&request);
+        self.answer(prompt, || self.api.chat_with_control(request, control))
+            .await
+    }
+
+    async fn completion_with_control(
+        &self,
+        request: CompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<CompletionResponse>, OllamaError> {
+        let prompt = completion_prompt(&request);
+        self.answer(prompt, || {
+            self.api.completion_with_control(request, control)
+        })
+        .await
+    }
+
+    async fn generate_embeddings(
+        &self,
+        request: EmbeddingsRequest,
+    ) -> Result<EmbeddingsResponse, OllamaError> {
+        self.api.generate_embeddings(request).await
+    }
+
+    async fn create(
+        &self,
+        request: CreateModelRequest,
+    ) -> Result<OllamaResponse<CreateModelResponse>, OllamaError> {
+        self.api.create(request).await
+    }
+
+    async fn list_local(&self) -> Result<ListLocalModelsResponse, OllamaError> {
+        self.api.list_local().await
+    }
+
+    async fn list_running(&self) -> Result<ListRunningModelsResponse, OllamaError> {
+        self.api.list_running().await
+    }
+
+    async fn show_info(&self, request: ShowModelRequest) -> Result<ShowModelResponse, OllamaError> {
+        self.api.show_info(request).await
+    }
+
+    async fn copy(&self, request: CopyModelRequest) -> Result<(), OllamaError> {
+        self.api.copy(request).await
+    }
+
+    async fn delete(&self, request: DeleteModelRequest) -> Result<(), OllamaError> {
+        self.api.delete(request).await
+    }
+
+    async fn pull(
+        &self,
+        request: PullModelRequest,
+    ) -> Result<OllamaResponse<PullModelResponse>, OllamaError> {
+        self.api.pull(request).await
+    }
+
+    async fn push(
+        &self,
+        request: PushModelRequest,
+    ) -> Result<OllamaResponse<PushModelResponse>, OllamaError> {
+        self.api.push(request).await
+    }
+}
+
+impl<A: fmt::Debug> fmt::Debug for SemanticCache<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SemanticCache")
+            .field("api", &self.api)
+            .field("embedding_model", &self.embedding_model)
+            .field("threshold", &self.threshold)
+            .field("max_entries", &self.max_entries)
+            .field("entries", &self.entries.lock().unwrap().entries.len())
+            .field("stats", &self.counters.stats())
+            .finish()
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::errors::OllamaError;
+
+/// Storage of cached responses: the chunks of a response body, by key.
+/// Implement it to keep responses in another store, e.g. Redis.
+pub trait CacheStore: Send + Sync {
+    /// The chunks stored for `key`, unless missing or expired.
+    fn get(&self, key: &str) -> Option<Vec<String>>;
+
+    /// Store `chunks` for `key`, expiring after `ttl` if any.
+    fn put(&self, key: &str, chunks: Vec<String>, ttl: Option<Duration>)
+        -> Result<(), OllamaError>;
+
+    fn remove(&self, key: &str) -> Result<(), OllamaError>;
+
+    fn clear(&self) -> Result<(), OllamaError>;
+}
+
+#[derive(Debug)]
+struct MemoryEntry {
+    chunks: Vec<String>,
+    expires_at: Option<Instant>,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    entries: HashMap<String, MemoryEntry>,
+    /// Keys by the tick of their last use, least recently used first.
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl MemoryState {
+    fn touch(&mut self, key: &str) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.recency.remove(&entry.last_used);
+            entry.last_used = tick;
+            self.recency.insert(tick, key.to_string());
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+        }
+    }
+}
+
+/// An in-memory store evicting the least recently used responses
+/// beyond `max_entries`.
+#[derive(Debug)]
+pub struct MemoryCache {
+    max_entries: usize,
+    state: Mutex<MemoryState>,
+}
+
+impl MemoryCache {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            max_entries: max_entries.max(1),
+            state: Mutex::default(),
+        }
+    }
+
+    /// The number of responses stored, expired ones included.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl CacheStore for MemoryCache {
+    fn get(&self, key: &str) -> Option<Vec<String>> {
+        let mut state = self.state.lock().unwrap();
+        let expires_at = state.entries.get(key)?.expires_at;
+        if expires_at.is_some_and(|expires_at| expires_at <= Instant::now()) {
+            state.remove(key);
+            return None;
+        }
+        state.touch(key);
+        state.entries.get(key).map(|entry| entry.chunks.clone())
+    }
+
+    fn put(
+        &self,
+        key: &str,
+        chunks: Vec<String>,
+        ttl: Option<Duration>,
+    ) -> Result<(), OllamaError> {
+        let mut state = self.state.lock().unwrap();
+        state.remove(key);
+        state.entries.insert(
+            key.to_string(),
+            MemoryEntry {
+                chunks,
+                expires_at: ttl.map(|ttl| Instant::now() + ttl),
+                last_used: 0,
+            },
+        );
+        state.touch(key);
+
+        while state.entries.len() > self.max_entries {
+            let Some((_, oldest)) = state.recency.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), OllamaError> {
+        self.state.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), OllamaError> {
+        *self.state.lock().unwrap() = MemoryState::default();
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    /// Expiry time in milliseconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<u64>,
+    chunks: Vec<String>,
+}
+
+/// A store keeping one JSON file per response in a directory. Once the files
+/// exceed `max_bytes`, the least recently used ones are deleted.
+#[derive(Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: Option<u64>,
+    lock: Mutex<()>,
+}
+
+impl DiskCache {
+    /// A store in `dir`, created if missing.
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self, OllamaError> {
+        fs::create_dir_all(dir.as_ref()).map_err(|e| OllamaError::IoError(e.to_string()))?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+            max_bytes: None,
+            lock: Mutex::default(),
+        })
+    }
+
+    /// Keep the total size of the stored files under `max_bytes`.
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.json", fnv1a(key.as_bytes())))
+    }
+
+    fn files(&self) -> Vec<(PathBuf, fs::Metadata)> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return vec![];
+        };
+        entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| fs::metadata(&path).ok().map(|meta| (path, meta)))
+            .collect()
+    }
+
+    /// Delete the least recently used files until the store fits in `max_bytes`.
+    fn evict(&self) {
+        let Some(max_bytes) = self.max_bytes else {
+            return;
+        };
+        let mut files = self.files();
+        let mut total: u64 = files.iter().map(|(_, meta)| meta.len()).sum();
+        files.sort_by_key(|(_, meta)| meta.modified().unwrap_or(UNIX_EPOCH));
+        for (path, meta) in files {
+            if total <= max_bytes {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                total -= meta.len();
+            }
+        }
+    }
+}
+
+impl CacheStore for DiskCache {
+    fn get(&self, key: &str) -> Option<Vec<String>> {
+        let _lock = self.lock.lock().unwrap();
+        let path = self.path(key);
+        let entry: DiskEntry = serde_json::from_slice(&fs::read(&path).ok()?).ok()?;
+        if entry.key != key {
+            return None;
+        }
+        if entry
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= unix_millis())
+        {
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+
+        // The modification time orders the files for eviction.
+        if let Ok(file) = fs::File::options().append(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(entry.chunks)
+    }
+
+    fn put(
+        &self,
+        key: &str,
+        chunks: Vec<String>,
+        ttl: Option<Duration>,
+    ) -> Result<(), OllamaError> {
+        let entry = DiskEntry {
+            key: key.to_string(),
+            expires_at: ttl.map(|ttl| unix_millis() + ttl.as_millis() as u64),
+            chunks,
+        };
+        let content =
+            serde_json::to_vec(&entry).map_err(|e| OllamaError::ParseError(e.to_string()))?;
+
+        let _lock = self.lock.lock().unwrap();
+        fs::write(self.path(key), content).map_err(|e| OllamaError::IoError(e.to_string()))?;
+        self.evict();
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), OllamaError> {
+        let _lock = self.lock.lock().unwrap();
+        match fs::remove_file(self.path(key)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(OllamaError::IoError(e.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn clear(&self) -> Result<(), OllamaError> {
+        let _lock = self.lock.lock().unwrap();
+        for (path, _) in self.files() {
+            fs::remove_file(path).map_err(|e| OllamaError::IoError(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// FNV-1a, a hash which stays the same across builds, for file names.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
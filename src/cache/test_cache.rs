@@ -0,0 +1,227 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+    use tokio_stream::StreamExt;
+
+    use crate::{
+        api::OllamaApi,
+        cache::{CacheStats, CacheStore, DiskCache, MemoryCache, ResponseCache},
+        chat_completion::{
+            message::{Message, Role},
+            request::ChatCompletionRequest,
+        },
+        model::generate_embeddings::EmbeddingsRequest,
+        options::Options,
+        testing::{chat_content, MockOllama, MockResponse},
+    };
+
+    fn request(seed: Option<i32>, temperature: Option<f32>) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: String::from("llama3"),
+            messages: vec![Message {
+                role: Role::User,
+                content: String::from("Hi"),
+                images: None,
+            }],
+            options: Options {
+                seed,
+                temperature,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn deterministic() -> ChatCompletionRequest {
+        request(Some(42), Some(0.0))
+    }
+
+    #[tokio::test]
+    async fn test_cache_hit() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["a", "b", "c"]),
+        );
+        let api = ResponseCache::new(mock.client(), MemoryCache::new(16));
+
+        assert_eq!(chat_content(&api, deterministic()).await, "abc");
+        assert_eq!(chat_content(&api, deterministic()).await, "abc");
+        assert_eq!(mock.requests_to("/api/chat").len(), 1);
+        assert_eq!(api.stats(), CacheStats { hits: 1, misses: 1 });
+    }
+
+    #[tokio::test]
+    async fn test_cache_skips_non_deterministic_requests() {
+        let mock = MockOllama::start().await;
+        let api = ResponseCache::new(mock.client(), MemoryCache::new(16));
+
+        for request in [request(Some(42), None), request(None, Some(0.0))] {
+            chat_content(&api, request.clone()).await;
+            chat_content(&api, request).await;
+        }
+        assert_eq!(mock.requests_to("/api/chat").len(), 4);
+        assert_eq!(api.stats(), CacheStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_cache_characters_split_across_chunks() {
+        let mock = MockOllama::start().await;
+        let line = |content: &str, done: bool| {
+            json!({
+                "model": "llama3",
+                "created_at": "2024-06-01T00:00:00Z",
+                "message": { "role": "assistant", "content": content },
+                "done": done,
+            })
+        };
+        let body = [line("Grüße", false), line("", true)]
+            .map(|line| format!("{line}\n"))
+            .concat()
+            .into_bytes();
+        // Split the two bytes of "ü".
+        let split = body.iter().position(|&b| b == 0xC3).unwrap() + 1;
+        mock.respond(
+            "/api/chat",
+            MockResponse::chunks(vec![body[..split].to_vec(), body[split..].to_vec()])
+                .with_delay(Duration::from_millis(20)),
+        );
+        let api = ResponseCache::new(mock.client(), MemoryCache::new(16));
+
+        assert_eq!(chat_content(&api, deterministic()).await, "Grüße");
+        assert_eq!(chat_content(&api, deterministic()).await, "Grüße");
+        assert_eq!(mock.requests_to("/api/chat").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_replays_streams() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["a", "b", "c"]),
+        );
+        let api = ResponseCache::new(mock.client(), MemoryCache::new(16));
+        chat_content(&api, deterministic()).await;
+
+        let mut stream = api
+            .chat(deterministic())
+            .await
+            .unwrap()
+            .as_stream()
+            .await
+            .unwrap();
+        let mut parts = vec![];
+        while let Some(item) = stream.next().await {
+            if let Some(message) = item.unwrap().message {
+                parts.push(message.content);
+            }
+        }
+        assert_eq!(parts.concat(), "abc");
+        assert!(parts.len() > 1);
+        assert_eq!(mock.requests_to("/api/chat").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_stores_complete_responses_only() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["a", "b", "c"]),
+        );
+        let api = ResponseCache::new(mock.client(), MemoryCache::new(16));
+
+        let mut stream = api
+            .chat(deterministic())
+            .await
+            .unwrap()
+            .as_stream()
+            .await
+            .unwrap();
+        stream.next().await.unwrap().unwrap();
+        drop(stream);
+
+        assert_eq!(chat_content(&api, deterministic()).await, "Hello from mock");
+        assert_eq!(mock.requests_to("/api/chat").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_ttl() {
+        let mock = MockOllama::start().await;
+        let api = ResponseCache::new(mock.client(), MemoryCache::new(16))
+            .with_ttl(Duration::from_millis(50));
+
+        chat_content(&api, deterministic()).await;
+        chat_content(&api, deterministic()).await;
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        chat_content(&api, deterministic()).await;
+        assert_eq!(mock.requests_to("/api/chat").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_embeddings() {
+        let mock = MockOllama::start().await;
+        let api = ResponseCache::new(mock.client(), MemoryCache::new(16));
+        let request = EmbeddingsRequest {
+            model: String::from("nomic-embed-text"),
+            prompt: String::from("Hello"),
+            ..Default::default()
+        };
+
+        let first = api.generate_embeddings(request.clone()).await.unwrap();
+        let second = api.generate_embeddings(request).await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(mock.requests_to("/api/embeddings").len(), 1);
+    }
+
+    #[test]
+    fn test_memory_cache_evicts_least_recently_used() {
+        let cache = MemoryCache::new(2);
+        cache.put("a", vec![String::from("1")], None).unwrap();
+        cache.put("b", vec![String::from("2")], None).unwrap();
+        assert!(cache.get("a").is_some());
+        cache.put("c", vec![String::from("3")], None).unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a"), Some(vec![String::from("1")]));
+        assert_eq!(cache.get("c"), Some(vec![String::from("3")]));
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache() {
+        let dir = std::env::temp_dir().join("pure_ollama_test_disk_cache");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mock = MockOllama::start().await;
+        let api = ResponseCache::new(mock.client(), DiskCache::new(&dir).unwrap());
+        chat_content(&api, deterministic()).await;
+
+        // A new cache on the same directory serves the stored response.
+        let api = ResponseCache::new(mock.client(), DiskCache::new(&dir).unwrap());
+        assert_eq!(chat_content(&api, deterministic()).await, "Hello from mock");
+        assert_eq!(mock.requests_to("/api/chat").len(), 1);
+
+        let cache = DiskCache::new(&dir).unwrap();
+        cache.clear().unwrap();
+        assert!(cache.get("anything").is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_disk_cache_max_bytes() {
+        let dir = std::env::temp_dir().join("pure_ollama_test_disk_cache_max_bytes");
+        let _ = std::fs::remove_dir_all(&dir);
+        let cache = DiskCache::new(&dir).unwrap().with_max_bytes(100);
+
+        let chunk = vec!["x".repeat(50)];
+        cache.put("a", chunk.clone(), None).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        cache.put("b", chunk.clone(), None).unwrap();
+
+        assert!(cache.get("a").is_none());
+        assert_eq!(cache.get("b"), Some(chunk));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
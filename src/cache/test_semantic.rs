@@ -0,0 +1,185 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        api::OllamaApi,
+        cache::{CacheStats, SemanticCache},
+        chat_completion::{
+            message::{Message, Role},
+            request::ChatCompletionRequest,
+        },
+        completion::request::CompletionRequest,
+        testing::{chat_content, MockOllama, MockResponse},
+    };
+
+    fn message(role: Role, content: &str) -> Message {
+        Message {
+            role,
+            content: content.to_string(),
+            images: None,
+        }
+    }
+
+    fn request(model: &str, system: &str, prompt: &str) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: model.to_string(),
+            messages: vec![message(Role::System, system), message(Role::User, prompt)],
+            ..Default::default()
+        }
+    }
+
+    fn embeddings(mock: &MockOllama, vectors: &[[f64; 2]]) {
+        for vector in vectors {
+            mock.respond(
+                "/api/embeddings",
+                MockResponse::json(json!({ "embedding": vector })),
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_semantic_cache_hit() {
+        let mock = MockOllama::start().await;
+        embeddings(&mock, &[[1.0, 0.0], [0.99, 0.05], [0.0, 1.0]]);
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["Paris", "."]),
+        );
+        let api = SemanticCache::new(mock.client(), "nomic-embed-text");
+
+        let capital = "What's the capital of France?";
+        assert_eq!(
+            chat_content(&api, request("llama3", "", capital)).await,
+            "Paris."
+        );
+        let similar = "What is the capital city of France?";
+        assert_eq!(
+            chat_content(&api, request("llama3", "", similar)).await,
+            "Paris."
+        );
+        let other = "How tall is the Eiffel tower?";
+        assert_eq!(
+            chat_content(&api, request("llama3", "", other)).await,
+            "Hello from mock"
+        );
+
+        assert_eq!(mock.requests_to("/api/chat").len(), 2);
+        assert_eq!(api.stats(), CacheStats { hits: 1, misses: 2 });
+        assert_eq!(api.len(), 2);
+        let embedded = mock.requests_to("/api/embeddings")[0].body.clone().unwrap();
+        assert_eq!(embedded["model"], "nomic-embed-text");
+        assert_eq!(embedded["prompt"], capital);
+    }
+
+    #[tokio::test]
+    async fn test_semantic_cache_scopes() {
+        let mock = MockOllama::start().await;
+        embeddings(&mock, &[[1.0, 0.0]; 4]);
+        let api = SemanticCache::new(mock.client(), "nomic-embed-text");
+
+        chat_content(&api, request("llama3", "Be brief.", "Hi")).await;
+        chat_content(&api, request("phi3", "Be brief.", "Hi")).await;
+        chat_content(&api, request("llama3", "Be verbose.", "Hi")).await;
+        assert_eq!(mock.requests_to("/api/chat").len(), 3);
+
+        // The same scope, with an implicit tag.
+        chat_content(&api, request("llama3:latest", "Be brief.", "Hi")).await;
+        assert_eq!(mock.requests_to("/api/chat").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_semantic_cache_history() {
+        let mock = MockOllama::start().await;
+        embeddings(&mock, &[[1.0, 0.0]; 4]);
+        let api = SemanticCache::new(mock.client(), "nomic-embed-text");
+        let chat = |messages: Vec<Message>| ChatCompletionRequest {
+            model: String::from("llama3"),
+            messages,
+            ..Default::default()
+        };
+
+        let asked = vec![
+            message(Role::User, "Hi"),
+            message(Role::Assistant, "Why?"),
+            message(Role::User, "Because"),
+        ];
+        // The same words, said by the other party.
+        let answered = vec![
+            message(Role::Assistant, "Hi"),
+            message(Role::User, "Why?"),
+            message(Role::User, "Because"),
+        ];
+        chat_content(&api, chat(asked.clone())).await;
+        chat_content(&api, chat(answered)).await;
+        assert_eq!(mock.requests_to("/api/chat").len(), 2);
+
+        chat_content(&api, chat(asked)).await;
+        assert_eq!(mock.requests_to("/api/chat").len(), 2);
+        let embedded = mock.requests_to("/api/embeddings")[0].body.clone().unwrap();
+        assert_eq!(embedded["prompt"], "Because");
+    }
+
+    #[tokio::test]
+    async fn test_semantic_cache_threshold() {
+        let mock = MockOllama::start().await;
+        embeddings(&mock, &[[1.0, 0.0], [1.0, 1.0]]);
+        let api = SemanticCache::new(mock.client(), "nomic-embed-text").with_threshold(0.8);
+
+        chat_content(&api, request("llama3", "", "Hi")).await;
+        chat_content(&api, request("llama3", "", "Hello")).await;
+        assert_eq!(mock.requests_to("/api/chat").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_semantic_cache_completion() {
+        let mock = MockOllama::start().await;
+        embeddings(&mock, &[[1.0, 0.0], [1.0, 0.0]]);
+        mock.respond(
+            "/api/generate",
+            MockResponse::completion_stream("llama3", &["4"], vec![1, 2]),
+        );
+        let api = SemanticCache::new(mock.client(), "nomic-embed-text");
+        let request = |prompt: &str| CompletionRequest {
+            model: String::from("llama3"),
+            prompt: prompt.to_string(),
+            ..Default::default()
+        };
+
+        for prompt in ["2 + 2 =", "2+2="] {
+            let response = api.completion(request(prompt)).await.unwrap();
+            assert_eq!(response.as_response().await.unwrap().response, "4");
+        }
+        assert_eq!(mock.requests_to("/api/generate").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_semantic_cache_without_embeddings() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/embeddings",
+            MockResponse::error(404, "model not found"),
+        );
+        let api = SemanticCache::new(mock.client(), "missing");
+
+        assert_eq!(
+            chat_content(&api, request("llama3", "", "Hi")).await,
+            "Hello from mock"
+        );
+        assert!(api.is_empty());
+        assert_eq!(api.stats(), CacheStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_semantic_cache_eviction() {
+        let mock = MockOllama::start().await;
+        embeddings(&mock, &[[1.0, 0.0], [0.0, 1.0], [1.0, 0.0]]);
+        let api = SemanticCache::new(mock.client(), "nomic-embed-text").with_max_entries(1);
+
+        chat_content(&api, request("llama3", "", "Hi")).await;
+        chat_content(&api, request("llama3", "", "Bye")).await;
+        chat_content(&api, request("llama3", "", "Hi")).await;
+        assert_eq!(api.len(), 1);
+        assert_eq!(mock.requests_to("/api/chat").len(), 3);
+    }
+}
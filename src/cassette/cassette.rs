@@ -0,0 +1,327 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use async_stream::stream;
+use bytes::Bytes;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_stream::{iter, StreamExt};
+
+use crate::{
+    client::{interceptor::InterceptedRequest, OllamaClient},
+    control::Watchdog,
+    errors::OllamaError,
+    response::response_from_stream,
+};
+
+/// Version of the cassette file format written by this crate.
+pub const CASSETTE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send every request to the server and record the interaction.
+    Record,
+
+    /// Answer requests with the recorded interactions.
+    Replay,
+}
+
+/// Request fields compared when looking for a recorded interaction. The method,
+/// the path and the `stream` flag of the request are always compared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchRules {
+    /// Compare the model name.
+    pub model: bool,
+
+    /// Compare the input: messages, prompt, system message, images and template.
+    pub messages: bool,
+
+    /// Compare the model options.
+    pub options: bool,
+
+    /// Compare the whole request body, overriding the other rules.
+    pub body: bool,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self {
+            model: true,
+            messages: true,
+            options: true,
+            body: false,
+        }
+    }
+}
+
+impl MatchRules {
+    const INPUT_FIELDS: [&'static str; 6] = [
+        "messages", "prompt", "system", "images", "template", "input",
+    ];
+
+    fn matches(&self, recorded: Option<&Value>, incoming: Option<&Value>) -> bool {
+        if self.body {
+            return recorded == incoming;
+        }
+
+        let same =
+            |key: &str| recorded.and_then(|v| v.get(key)) == incoming.and_then(|v| v.get(key));
+        let mut fields = vec!["stream"];
+        if self.model {
+            fields.extend(["model", "name"]);
+        }
+        if self.messages {
+            fields.extend(Self::INPUT_FIELDS);
+        }
+        if self.options {
+            fields.push("options");
+        }
+
+        fields.into_iter().all(same)
+    }
+}
+
+/// One recorded request and its response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method: String,
+
+    pub path: String,
+
+    /// The serialized request body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<Value>,
+
+    /// The chunks of the response body as received, NDJSON lines for streaming endpoints.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub response: Vec<String>,
+
+    /// The error body returned by the server instead of a response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// True if the error was returned with a server error status (5xx).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub server_error: bool,
+
+    #[serde(skip)]
+    replayed: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CassetteFile {
+    version: u32,
+    interactions: Vec<Interaction>,
+}
+
+/// VCR-style record and replay of Ollama interactions. Attach a cassette to a client
+/// with [`crate::client::OllamaClientBuilder::cassette`]: in record mode, every
+/// interaction is written to the cassette file once its response has been fully read;
+/// in replay mode, requests are answered from the file without contacting the server.
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    rules: MatchRules,
+    strict: bool,
+    interactions: Arc<Mutex<Vec<Interaction>>>,
+}
+
+impl Cassette {
+    /// Record a new cassette to `path`, replacing any existing file.
+    pub fn record(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            mode: CassetteMode::Record,
+            rules: MatchRules::default(),
+            strict: true,
+            interactions: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    /// Replay the cassette stored at `path`.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, OllamaError> {
+        let content =
+            fs::read_to_string(path.as_ref()).map_err(|e| OllamaError::IoError(e.to_string()))?;
+        let file: CassetteFile =
+            serde_json::from_str(&content).map_err(|e| OllamaError::ParseError(e.to_string()))?;
+        if file.version == 0 || file.version > CASSETTE_FORMAT_VERSION {
+            return Err(OllamaError::ParseError(format!(
+                "unsupported cassette format version: {}",
+                file.version
+            )));
+        }
+
+        Ok(Self {
+            interactions: Arc::new(Mutex::new(file.interactions)),
+            mode: CassetteMode::Replay,
+            ..Self::record(path)
+        })
+    }
+
+    /// Replay the cassette at `path` if it exists, otherwise record it.
+    pub fn replay_or_record(path: impl AsRef<Path>) -> Result<Self, OllamaError> {
+        match path.as_ref().exists() {
+            true => Self::replay(path),
+            false => Ok(Self::record(path)),
+        }
+    }
+
+    /// Set the rules used to match requests with recorded interactions.
+    pub fn with_rules(mut self, rules: MatchRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// In strict mode (the default), replaying fails on requests matching no
+    /// recorded interaction; otherwise such requests are sent to the server.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    #[inline]
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// The interactions recorded or loaded so far.
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.interactions.lock().unwrap().clone()
+    }
+
+    /// Write the interactions to the cassette file.
+    pub fn save(&self) -> Result<(), OllamaError> {
+        let file = CassetteFile {
+            version: CASSETTE_FORMAT_VERSION,
+            interactions: self.interactions(),
+        };
+        let content = serde_json::to_string_pretty(&file)
+            .map_err(|e| OllamaError::ParseError(e.to_string()))?;
+        fs::write(&self.path, content).map_err(|e| OllamaError::IoError(e.to_string()))
+    }
+
+    pub(crate) async fn execute(
+        &self,
+        client: &OllamaClient,
+        request: &InterceptedRequest,
+        watchdog: &Watchdog,
+        streaming: bool,
+    ) -> Result<reqwest::Response, OllamaError> {
+        let (method, path) = (&request.method, request.path.as_str());
+        if self.mode == CassetteMode::Replay {
+            if let Some(interaction) = self.take(method, path, request.body.as_ref()) {
+                return replay(interaction);
+            }
+            if self.strict {
+                return Err(OllamaError::RequestError(format!(
+                    "no recorded interaction matches {method} {path}"
+                )));
+            }
+            return client.send_with_retry(request, watchdog, streaming).await;
+        }
+
+        let interaction = Interaction {
+            method: method.to_string(),
+            path: path.to_string(),
+            request: request.body.clone(),
+            response: vec![],
+            error: None,
+            server_error: false,
+            replayed: false,
+        };
+
+        let result = client.send_with_retry(request, watchdog, streaming).await;
+        match result {
+            Ok(response) => Ok(self.tee(interaction, response)),
+            Err(OllamaError::OllamaError(err_msg)) => {
+                self.push(Interaction {
+                    error: Some(err_msg.clone()),
+                    ..interaction
+                })?;
+                Err(OllamaError::OllamaError(err_msg))
+            }
+            Err(OllamaError::ServerError(err_msg)) => {
+                self.push(Interaction {
+                    error: Some(err_msg.clone()),
+                    server_error: true,
+                    ..interaction
+                })?;
+                Err(OllamaError::ServerError(err_msg))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Find the first interaction matching the request which hasn't been replayed yet,
+    /// falling back to the last matching one.
+    fn take(&self, method: &Method, path: &str, body: Option<&Value>) -> Option<Interaction> {
+        let mut interactions = self.interactions.lock().unwrap();
+        let mut matching = interactions.iter_mut().filter(|interaction| {
+            interaction.method == method.as_str()
+                && interaction.path == path
+                && self.rules.matches(interaction.request.as_ref(), body)
+        });
+
+        let mut last = None;
+        for interaction in &mut matching {
+            if !interaction.replayed {
+                interaction.replayed = true;
+                return Some(interaction.clone());
+            }
+            last = Some(interaction.clone());
+        }
+        last
+    }
+
+    fn push(&self, interaction: Interaction) -> Result<(), OllamaError> {
+        self.interactions.lock().unwrap().push(interaction);
+        self.save()
+    }
+
+    /// Pass the body of `response` through while recording its chunks. The interaction
+    /// is saved once the body has been read to the end.
+    fn tee(&self, mut interaction: Interaction, response: reqwest::Response) -> reqwest::Response {
+        let status = response.status();
+        let mut input = response.bytes_stream();
+        let cassette = self.clone();
+
+        let recorded = stream! {
+            while let Some(item) = input.next().await {
+                match item {
+                    Ok(chunk) => {
+                        interaction.response.push(String::from_utf8_lossy(&chunk).into_owned());
+                        yield Ok(chunk);
+                    }
+                    Err(e) => {
+                        yield Err(e);
+                        return;
+                    }
+                }
+            }
+            // Failing to write the cassette must not break the recorded call.
+            let _ = cassette.push(interaction);
+        };
+
+        response_from_stream(status, recorded)
+    }
+}
+
+fn replay(interaction: Interaction) -> Result<reqwest::Response, OllamaError> {
+    if let Some(err_msg) = interaction.error {
+        return Err(match interaction.server_error {
+            true => OllamaError::ServerError(err_msg),
+            false => OllamaError::OllamaError(err_msg),
+        });
+    }
+
+    let chunks = interaction
+        .response
+        .into_iter()
+        .map(|chunk| Ok::<_, std::convert::Infallible>(Bytes::from(chunk)));
+    Ok(response_from_stream(StatusCode::OK, iter(chunks)))
+}
@@ -0,0 +1,6 @@
+pub mod cassette;
+
+// test module
+mod test_cassette;
+
+pub use cassette::{Cassette, CassetteMode, Interaction, MatchRules, CASSETTE_FORMAT_VERSION};
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cassette::{Cassette, CassetteMode, MatchRules},
+        chat_completion::{
+            message::{MessageBuilder, Role},
+            request::{ChatCompletionRequest, ChatCompletionRequestBuilder},
+        },
+        client::OllamaClientBuilder,
+        errors::OllamaError,
+        options::OptionsConstructor,
+        testing::{MockOllama, MockResponse},
+    };
+
+    fn chat_request(content: &str, seed: i32) -> ChatCompletionRequest {
+        ChatCompletionRequestBuilder::default()
+            .model("llama3")
+            .messages(vec![MessageBuilder::default()
+                .role(Role::User)
+                .content(content)
+                .build()
+                .unwrap()])
+            .seed(seed)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let path = std::env::temp_dir().join("pure_ollama_test_cassette.json");
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["Re", "corded"]),
+        );
+        mock.respond("/api/chat", MockResponse::error(400, "invalid options"));
+
+        let recorder = OllamaClientBuilder::default()
+            .host(mock.url())
+            .cassette(Cassette::record(&path))
+            .build()
+            .unwrap();
+        let response = recorder.chat(chat_request("Hello", 1)).await.unwrap();
+        let recorded = response.as_response().await.unwrap();
+        let err = recorder.chat(chat_request("Oops", 1)).await.err().unwrap();
+        drop(mock);
+
+        let cassette = Cassette::replay(&path).unwrap();
+        assert_eq!(cassette.mode(), CassetteMode::Replay);
+        assert_eq!(cassette.interactions().len(), 2);
+
+        let player = OllamaClientBuilder::default()
+            .host("http://127.0.0.1:1")
+            .cassette(cassette)
+            .build()
+            .unwrap();
+        let response = player.chat(chat_request("Hello", 1)).await.unwrap();
+        let replayed = response.as_response().await.unwrap();
+        assert_eq!(replayed.message, recorded.message);
+        assert_eq!(replayed.eval_count, recorded.eval_count);
+        assert_eq!(player.chat(chat_request("Oops", 1)).await.err(), Some(err));
+
+        let unmatched = player.chat(chat_request("Hello", 2)).await.err().unwrap();
+        assert!(matches!(unmatched, OllamaError::RequestError(_)));
+
+        let rules = MatchRules {
+            options: false,
+            ..Default::default()
+        };
+        let player = OllamaClientBuilder::default()
+            .host("http://127.0.0.1:1")
+            .cassette(Cassette::replay(&path).unwrap().with_rules(rules))
+            .build()
+            .unwrap();
+        assert!(player.chat(chat_request("Hello", 2)).await.is_ok());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,7 +1,6 @@
 use super::{request::ChatCompletionRequest, response::ChatResponse};
 use crate::{
-    errors::OllamaError,
-    response::{check_response_valid, OllamaResponse},
+    client::OllamaClient, control::RequestControl, errors::OllamaError, response::OllamaResponse,
 };
 
 /// Generate the next message in a chat with a provided model. This is a streaming endpoint,
@@ -10,13 +9,16 @@ use crate::{
 pub async fn chat(
     request: ChatCompletionRequest,
 ) -> Result<OllamaResponse<ChatResponse>, OllamaError> {
-    let url = "http://localhost:11434/api/chat";
-    let resp = reqwest::Client::default()
-        .post(url)
-        .json(&request)
-        .send()
-        .await;
+    chat_with_control(request, RequestControl::default()).await
+}
 
-    let response = check_response_valid(resp).await?;
-    Ok(response.into())
+/// Same as [`chat`], aborting the request with [`OllamaError::Cancelled`] or
+/// [`OllamaError::Timeout`] according to `control`.
+pub async fn chat_with_control(
+    request: ChatCompletionRequest,
+    control: RequestControl,
+) -> Result<OllamaResponse<ChatResponse>, OllamaError> {
+    OllamaClient::default()
+        .chat_with_control(request, control)
+        .await
 }
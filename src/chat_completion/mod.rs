@@ -6,4 +6,4 @@ pub mod response;
 // test module
 mod test_chat_completion;
 
-pub use chat_completion::chat;
+pub use chat_completion::{chat, chat_with_control};
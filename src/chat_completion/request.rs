@@ -1,5 +1,5 @@
 use derive_builder::Builder;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     format::Format,
@@ -8,7 +8,7 @@ use crate::{
 
 use super::message::Message;
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Builder)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Builder)]
 pub struct ChatCompletionRequest {
     /// The model name.
     #[builder(setter(into))]
@@ -30,7 +30,7 @@ pub struct ChatCompletionRequest {
         ty = "crate::options::OptionsBuilder",
         build = r#"self.options.build().unwrap()"#
     ))]
-    #[serde(skip_serializing_if = "crate::options::Options::is_default")]
+    #[serde(default, skip_serializing_if = "crate::options::Options::is_default")]
     pub options: Options,
 
     /// If false the response will be returned as a single response object,
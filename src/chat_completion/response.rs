@@ -20,6 +20,10 @@ pub struct ChatResponse {
 
     pub done: bool,
 
+    /// Why generation stopped, set on the final chunk: `stop`, or `length`
+    /// once `num_predict` tokens were generated.
+    pub done_reason: Option<String>,
+
     pub message: Option<Message>,
 
     /// Time spent generating the response.
@@ -41,6 +45,19 @@ pub struct ChatResponse {
     pub eval_duration: Option<usize>,
 }
 
+impl ChatResponse {
+    /// Generation speed in tokens per second, computed from `eval_count` and
+    /// `eval_duration` which are only set on the final chunk.
+    pub fn tokens_per_second(&self) -> Option<f64> {
+        match (self.eval_count, self.eval_duration) {
+            (Some(count), Some(duration)) if duration > 0 => {
+                Some(count as f64 / duration as f64 * 1e9)
+            }
+            _ => None,
+        }
+    }
+}
+
 #[async_trait]
 impl StreamHandler for ChatResponse {
     async fn adapt_stream(
@@ -3,10 +3,14 @@ mod tests {
     use tokio::io::{stdout, AsyncWriteExt};
     use tokio_stream::StreamExt;
 
-    use crate::chat_completion::{
-        chat,
-        message::{MessageBuilder, MessageBuilderError, Role},
-        request::ChatCompletionRequestBuilder,
+    use crate::{
+        chat_completion::{
+            chat,
+            message::{MessageBuilder, MessageBuilderError, Role},
+            request::ChatCompletionRequestBuilder,
+        },
+        errors::OllamaError,
+        testing::{MockOllama, MockResponse},
     };
 
     #[test]
@@ -47,6 +51,54 @@ mod tests {
         assert_eq!(resp, Err(MessageBuilderError::UninitializedField("role")));
     }
 
+    #[tokio::test]
+    async fn test_chat_mock() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["Hey", "!"]),
+        );
+
+        let request = ChatCompletionRequestBuilder::default()
+            .model("llama3")
+            .messages(vec![MessageBuilder::default()
+                .role(Role::User)
+                .content("Hello")
+                .build()
+                .unwrap()])
+            .build()
+            .unwrap();
+
+        let response = mock.client().chat(request.clone()).await.unwrap();
+        let response = response.as_response().await.unwrap();
+        assert_eq!(response.eval_count, Some(2));
+        assert_eq!(response.tokens_per_second(), Some(1000.0));
+        assert_eq!(response.message.unwrap().content, "Hey!");
+
+        let received = mock.requests_to("/api/chat");
+        assert_eq!(received.len(), 1);
+        assert_eq!(
+            received[0].body,
+            Some(serde_json::to_value(&request).unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chat_mock_error() {
+        let mock = MockOllama::start().await;
+        mock.respond("/api/chat", MockResponse::error(400, "invalid options"));
+
+        let request = ChatCompletionRequestBuilder::default()
+            .model("llama3")
+            .messages(vec![])
+            .build()
+            .unwrap();
+
+        let err = mock.client().chat(request).await.err().unwrap();
+        let err_msg = String::from("{\"error\":\"invalid options\"}");
+        assert_eq!(err, OllamaError::OllamaError(err_msg));
+    }
+
     #[ignore]
     #[tokio::test]
     async fn test_chat_non_stream() {
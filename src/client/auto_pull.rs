@@ -0,0 +1,98 @@
+use std::{fmt, future::Future, sync::Arc};
+
+use tokio_stream::StreamExt;
+
+use super::OllamaClient;
+use crate::{
+    errors::OllamaError,
+    model::pull::{PullModelRequestBuilder, PullModelResponse},
+};
+
+type ProgressCallback = Arc<dyn Fn(&PullModelResponse) + Send + Sync>;
+
+/// Opt-in client policy pulling a model the server doesn't have yet,
+/// then sending the original request once more.
+#[derive(Clone, Default)]
+pub struct AutoPull {
+    on_progress: Option<ProgressCallback>,
+    insecure: bool,
+}
+
+impl AutoPull {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `callback` with every progress update of the pull.
+    pub fn on_progress(
+        mut self,
+        callback: impl Fn(&PullModelResponse) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Allow insecure connections to the library while pulling.
+    pub fn insecure(mut self, insecure: bool) -> Self {
+        self.insecure = insecure;
+        self
+    }
+}
+
+impl fmt::Debug for AutoPull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AutoPull")
+            .field("on_progress", &self.on_progress.is_some())
+            .field("insecure", &self.insecure)
+            .finish()
+    }
+}
+
+impl OllamaClient {
+    /// Pull `model`, reporting progress to `auto_pull`. Fails if the
+    /// stream ends without the final "success" status.
+    pub async fn pull_model(&self, model: &str, auto_pull: &AutoPull) -> Result<(), OllamaError> {
+        let request = PullModelRequestBuilder::default()
+            .name(model)
+            .insecure(auto_pull.insecure)
+            .build()
+            .unwrap();
+
+        let mut stream = self.pull(request).await?.as_stream().await?;
+        let mut status = String::default();
+        while let Some(item) = stream.next().await {
+            let item = item?;
+            if let Some(callback) = &auto_pull.on_progress {
+                callback(&item);
+            }
+            status = item.status;
+        }
+
+        if status != "success" {
+            return Err(OllamaError::OllamaError(format!(
+                "failed to pull model '{model}': {status}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Run `call`, and if the server reports `model` as missing while auto pull is
+    /// enabled, pull it and run `call` once more.
+    pub(crate) async fn with_auto_pull<T, F, Fut>(
+        &self,
+        model: &str,
+        call: F,
+    ) -> Result<T, OllamaError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, OllamaError>>,
+    {
+        match (call().await, &self.auto_pull) {
+            (Err(e), Some(auto_pull)) if e.is_model_not_found() => {
+                self.pull_model(model, auto_pull).await?;
+                call().await
+            }
+            (result, _) => result,
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::future::Future;
+
+use futures_util::stream::{self, Stream, StreamExt};
+
+use super::OllamaClient;
+use crate::{
+    chat_completion::{request::ChatCompletionRequest, response::ChatResponse},
+    completion::{request::CompletionRequest, response::CompletionResponse},
+    errors::OllamaError,
+};
+
+impl OllamaClient {
+    /// Generate a chat message for every request, running up to `concurrency`
+    /// requests at a time. Results are returned in the order of `requests`.
+    pub async fn chat_batch(
+        &self,
+        requests: Vec<ChatCompletionRequest>,
+        concurrency: usize,
+    ) -> Vec<Result<ChatResponse, OllamaError>> {
+        in_order(self.chat_batch_stream(requests, concurrency)).await
+    }
+
+    /// Same as [`OllamaClient::chat_batch`], failing on the first error.
+    /// Requests in flight are then cancelled and the remaining ones aren't sent.
+    pub async fn try_chat_batch(
+        &self,
+        requests: Vec<ChatCompletionRequest>,
+        concurrency: usize,
+    ) -> Result<Vec<ChatResponse>, OllamaError> {
+        fail_fast(self.chat_batch_stream(requests, concurrency)).await
+    }
+
+    /// Same as [`OllamaClient::chat_batch`], yielding every result with the index
+    /// of its request as soon as it completes.
+    pub fn chat_batch_stream(
+        &self,
+        requests: Vec<ChatCompletionRequest>,
+        concurrency: usize,
+    ) -> impl Stream<Item = (usize, Result<ChatResponse, OllamaError>)> + Send + 'static {
+        let client = self.clone();
+        batch_stream(requests, concurrency, move |request| {
+            let client = client.clone();
+            async move { client.chat(request).await?.as_response().await }
+        })
+    }
+
+    /// Generate a completion for every request, running up to `concurrency`
+    /// requests at a time. Results are returned in the order of `requests`.
+    pub async fn completion_batch(
+        &self,
+        requests: Vec<CompletionRequest>,
+        concurrency: usize,
+    ) -> Vec<Result<CompletionResponse, OllamaError>> {
+        in_order(self.completion_batch_stream(requests, concurrency)).await
+    }
+
+    /// Same as [`OllamaClient::completion_batch`], failing on the first error.
+    /// Requests in flight are then cancelled and the remaining ones aren't sent.
+    pub async fn try_completion_batch(
+        &self,
+        requests: Vec<CompletionRequest>,
+        concurrency: usize,
+    ) -> Result<Vec<CompletionResponse>, OllamaError> {
+        fail_fast(self.completion_batch_stream(requests, concurrency)).await
+    }
+
+    /// Same as [`OllamaClient::completion_batch`], yielding every result with the
+    /// index of its request as soon as it completes.
+    pub fn completion_batch_stream(
+        &self,
+        requests: Vec<CompletionRequest>,
+        concurrency: usize,
+    ) -> impl Stream<Item = (usize, Result<CompletionResponse, OllamaError>)> + Send + 'static {
+        let client = self.clone();
+        batch_stream(requests, concurrency, move |request| {
+            let client = client.clone();
+            async move { client.completion(request).await?.as_response().await }
+        })
+    }
+}
+
+fn batch_stream<R, T, F, Fut>(
+    requests: Vec<R>,
+    concurrency: usize,
+    call: F,
+) -> impl Stream<Item = (usize, Result<T, OllamaError>)> + Send + 'static
+where
+    R: Send + 'static,
+    F: Fn(R) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T, OllamaError>> + Send + 'static,
+{
+    stream::iter(requests.into_iter().enumerate())
+        .map(move |(index, request)| {
+            let response = call(request);
+            async move { (index, response.await) }
+        })
+        .buffer_unordered(concurrency.max(1))
+}
+
+async fn in_order<T>(
+    results: impl Stream<Item = (usize, Result<T, OllamaError>)>,
+) -> Vec<Result<T, OllamaError>> {
+    let mut results: Vec<_> = results.collect().await;
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+async fn fail_fast<T>(
+    results: impl Stream<Item = (usize, Result<T, OllamaError>)>,
+) -> Result<Vec<T>, OllamaError> {
+    let mut results = Box::pin(results);
+    let mut responses = vec![];
+    while let Some((index, result)) = results.next().await {
+        responses.push((index, result?));
+    }
+    responses.sort_by_key(|(index, _)| *index);
+    Ok(responses
+        .into_iter()
+        .map(|(_, response)| response)
+        .collect())
+}
@@ -0,0 +1,210 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+use crate::errors::OllamaError;
+
+type StateCallback = Arc<dyn Fn(CircuitState) + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent to the server.
+    Closed,
+
+    /// The server failed repeatedly, requests are rejected without being sent.
+    Open,
+
+    /// The open duration has elapsed, a single probe request is let through
+    /// to decide whether to close the circuit again.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct BreakerState {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+/// Opt-in client policy which stops sending requests to a server after
+/// consecutive failures, so that callers fail fast with
+/// [`OllamaError::CircuitOpen`] instead of waiting on a wedged server.
+///
+/// Connection errors, server errors (5xx), failed streams and first chunk timeouts
+/// count as failures; any other response counts as a success. Clones of a client
+/// share the state of its circuit breaker.
+///
+/// A breaker tracks a single server: give each client its own, e.g. each client
+/// of an [`OllamaPool`](crate::pool::OllamaPool), since clients sharing clones of
+/// one breaker would all be cut off by the failures of any of them.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    open_duration: Duration,
+    on_state_change: Option<StateCallback>,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(30),
+            on_state_change: None,
+            state: Arc::new(Mutex::new(BreakerState {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                probing: false,
+            })),
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// A circuit breaker opening after 5 consecutive failures, for 30 seconds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the circuit after `threshold` consecutive failures.
+    pub fn failure_threshold(mut self, threshold: u32) -> Self {
+        self.failure_threshold = threshold.max(1);
+        self
+    }
+
+    /// Reject requests for `duration` once open, before letting a probe through.
+    pub fn open_duration(mut self, duration: Duration) -> Self {
+        self.open_duration = duration;
+        self
+    }
+
+    /// Call `callback` with the new state every time the circuit changes state.
+    pub fn on_state_change(
+        mut self,
+        callback: impl Fn(CircuitState) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_state_change = Some(Arc::new(callback));
+        self
+    }
+
+    /// The current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        self.state.lock().unwrap().state
+    }
+
+    /// The number of failures since the last success.
+    pub fn consecutive_failures(&self) -> u32 {
+        self.state.lock().unwrap().consecutive_failures
+    }
+
+    /// Admit a request, `None` while the circuit is open. The outcome of the
+    /// request must be reported to the returned guard.
+    pub(crate) fn admit(&self) -> Option<CircuitCall> {
+        let mut state = self.state.lock().unwrap();
+        let probe = match state.state {
+            CircuitState::Closed => false,
+            CircuitState::Open => {
+                let elapsed = state.opened_at.map(|opened| opened.elapsed());
+                if elapsed.is_some_and(|elapsed| elapsed < self.open_duration) {
+                    return None;
+                }
+                state.state = CircuitState::HalfOpen;
+                state.probing = true;
+                drop(state);
+                self.notify(CircuitState::HalfOpen);
+                true
+            }
+            CircuitState::HalfOpen if state.probing => return None,
+            CircuitState::HalfOpen => {
+                state.probing = true;
+                true
+            }
+        };
+
+        Some(CircuitCall {
+            breaker: self.clone(),
+            probe,
+            reported: false,
+        })
+    }
+
+    fn report(&self, success: bool, probe: bool) {
+        let mut state = self.state.lock().unwrap();
+        if probe {
+            state.probing = false;
+        }
+
+        let next = if success {
+            state.consecutive_failures = 0;
+            CircuitState::Closed
+        } else {
+            state.consecutive_failures += 1;
+            if probe || state.consecutive_failures >= self.failure_threshold {
+                state.opened_at = Some(Instant::now());
+                CircuitState::Open
+            } else {
+                state.state
+            }
+        };
+
+        if state.state != next {
+            state.state = next;
+            drop(state);
+            self.notify(next);
+        }
+    }
+
+    /// Call the callback outside of the lock, so that it can query the breaker.
+    fn notify(&self, state: CircuitState) {
+        if let Some(callback) = &self.on_state_change {
+            callback(state);
+        }
+    }
+}
+
+/// The error of a request to `host` rejected by its open circuit.
+pub(crate) fn open_error(host: &str) -> OllamaError {
+    OllamaError::CircuitOpen(format!("too many failures from {host}"))
+}
+
+impl fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("failure_threshold", &self.failure_threshold)
+            .field("open_duration", &self.open_duration)
+            .field("on_state_change", &self.on_state_change.is_some())
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+/// A request admitted by a [`CircuitBreaker`]. Dropping it without reporting
+/// an outcome, e.g. when the request is cancelled, leaves the circuit unchanged.
+pub(crate) struct CircuitCall {
+    breaker: CircuitBreaker,
+    probe: bool,
+    reported: bool,
+}
+
+impl CircuitCall {
+    pub(crate) fn report(&mut self, success: bool) {
+        if !self.reported {
+            self.reported = true;
+            self.breaker.report(success, self.probe);
+        }
+    }
+}
+
+impl Drop for CircuitCall {
+    fn drop(&mut self) {
+        if !self.reported && self.probe {
+            self.breaker.state.lock().unwrap().probing = false;
+        }
+    }
+}
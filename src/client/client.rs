@@ -0,0 +1,375 @@
+use std::sync::Arc;
+
+use derive_builder::Builder;
+use reqwest::{header::HeaderMap, Method, StatusCode};
+use serde::Serialize;
+use tokio_stream::{iter, StreamExt};
+
+#[cfg(feature = "tracing")]
+use super::telemetry;
+use super::{
+    auto_pull::AutoPull,
+    circuit_breaker::{open_error, CircuitBreaker},
+    interceptor::{InterceptedRequest, Interceptor, InterceptorChain},
+    limits::{ConcurrencyLimiter, Permit},
+    retry::{Failure, RetryPolicy},
+};
+use crate::{
+    cassette::Cassette,
+    chat_completion::{request::ChatCompletionRequest, response::ChatResponse},
+    completion::{request::CompletionRequest, response::CompletionResponse},
+    control::{RequestControl, Watchdog},
+    errors::OllamaError,
+    response::OllamaResponse,
+};
+
+/// Address of a local Ollama server.
+pub const DEFAULT_HOST: &str = "http://localhost:11434";
+
+/// A client for one Ollama server.
+#[derive(Debug, Clone, Builder)]
+pub struct OllamaClient {
+    /// Base URL of the Ollama server.
+    #[builder(setter(into), default = "String::from(DEFAULT_HOST)")]
+    host: String,
+
+    /// The HTTP client used to send requests.
+    #[builder(default)]
+    pub(crate) http: reqwest::Client,
+
+    /// Retry policy applied to transient failures.
+    #[builder(default)]
+    retry_policy: RetryPolicy,
+
+    /// Pull missing models on demand, disabled by default.
+    #[builder(setter(strip_option), default)]
+    pub(crate) auto_pull: Option<AutoPull>,
+
+    /// Stop sending requests after consecutive failures, disabled by default.
+    #[builder(setter(strip_option), default)]
+    circuit_breaker: Option<CircuitBreaker>,
+
+    /// Limit the number of generation requests in flight, disabled by default.
+    #[builder(setter(strip_option), default)]
+    concurrency_limiter: Option<ConcurrencyLimiter>,
+
+    /// Sent as a bearer token with every request, for servers behind an
+    /// authenticating proxy.
+    #[builder(setter(into, strip_option), default)]
+    pub(crate) api_key: Option<String>,
+
+    /// Record interactions to, or replay them from, a cassette.
+    #[builder(setter(strip_option), default)]
+    cassette: Option<Cassette>,
+
+    /// Hooks into every request and response, see [`Interceptor`].
+    #[builder(setter(custom), default)]
+    interceptors: InterceptorChain,
+}
+
+impl OllamaClientBuilder {
+    /// Add `interceptor` to the end of the interceptor chain.
+    pub fn interceptor(&mut self, interceptor: impl Interceptor + 'static) -> &mut Self {
+        self.interceptors
+            .get_or_insert_with(InterceptorChain::default)
+            .push(Arc::new(interceptor));
+        self
+    }
+}
+
+impl Default for OllamaClient {
+    fn default() -> Self {
+        OllamaClientBuilder::default().build().unwrap()
+    }
+}
+
+impl OllamaClient {
+    pub fn new(host: impl Into<String>) -> Self {
+        OllamaClientBuilder::default().host(host).build().unwrap()
+    }
+
+    #[inline]
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    #[inline]
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    #[inline]
+    pub fn circuit_breaker(&self) -> Option<&CircuitBreaker> {
+        self.circuit_breaker.as_ref()
+    }
+
+    #[inline]
+    pub fn concurrency_limiter(&self) -> Option<&ConcurrencyLimiter> {
+        self.concurrency_limiter.as_ref()
+    }
+
+    /// Generate the next message in a chat with a provided model.
+    /// See [`crate::chat_completion::chat`].
+    pub async fn chat(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<OllamaResponse<ChatResponse>, OllamaError> {
+        self.chat_with_control(request, RequestControl::default())
+            .await
+    }
+
+    /// Same as [`OllamaClient::chat`], aborting the request according to `control`.
+    pub async fn chat_with_control(
+        &self,
+        request: ChatCompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<ChatResponse>, OllamaError> {
+        let model = request.model.clone();
+        let permit = self.acquire_slot(&model, &control).await?;
+        let response = self
+            .with_auto_pull(&model, || self.post("/api/chat", &request, &control, true))
+            .await?;
+        Ok(match permit {
+            Some(permit) => response.hold(permit),
+            None => response,
+        })
+    }
+
+    /// Generate a response for a given prompt with a provided model.
+    /// See [`crate::completion::completion::completion`].
+    pub async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<OllamaResponse<CompletionResponse>, OllamaError> {
+        self.completion_with_control(request, RequestControl::default())
+            .await
+    }
+
+    /// Same as [`OllamaClient::completion`], aborting the request according to `control`.
+    pub async fn completion_with_control(
+        &self,
+        request: CompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<CompletionResponse>, OllamaError> {
+        let model = request.model.clone();
+        let permit = self.acquire_slot(&model, &control).await?;
+        let response = self
+            .with_auto_pull(&model, || {
+                self.post("/api/generate", &request, &control, true)
+            })
+            .await?;
+        Ok(match permit {
+            Some(permit) => response.hold(permit),
+            None => response,
+        })
+    }
+
+    /// Send `body` to `path` as is, for proxies forwarding requests whose fields
+    /// they don't need to understand. Streamed unless `stream` is false, through
+    /// the concurrency limiter and auto pull like typed calls.
+    #[cfg(feature = "proxy")]
+    pub(crate) async fn forward(
+        &self,
+        path: &str,
+        body: serde_json::Value,
+    ) -> Result<reqwest::Response, OllamaError> {
+        let model = body["model"].as_str().unwrap_or_default().to_string();
+        let streaming = body["stream"].as_bool().unwrap_or(true);
+        let control = RequestControl::default();
+        let permit = self.acquire_slot(&model, &control).await?;
+        let response: OllamaResponse<serde_json::Value> = self
+            .with_auto_pull(&model, || {
+                self.execute(Method::POST, path, Some(body.clone()), &control, streaming)
+            })
+            .await?;
+        let response = match permit {
+            Some(permit) => response.hold(permit),
+            None => response,
+        };
+        Ok(response.raw_response())
+    }
+
+    /// Wait for the concurrency limiter, if any, to let a request to `model` through.
+    pub(crate) async fn acquire_slot(
+        &self,
+        model: &str,
+        control: &RequestControl,
+    ) -> Result<Option<Permit>, OllamaError> {
+        match &self.concurrency_limiter {
+            Some(limiter) => limiter.acquire(model, control).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("{}{}", self.host.trim_end_matches('/'), path)
+    }
+
+    /// Send `body` to `path`, see [`OllamaClient::execute`].
+    pub(crate) async fn post<T>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+        control: &RequestControl,
+        streaming: bool,
+    ) -> Result<OllamaResponse<T>, OllamaError> {
+        let body =
+            serde_json::to_value(body).map_err(|e| OllamaError::ParseError(e.to_string()))?;
+        self.execute(Method::POST, path, Some(body), control, streaming)
+            .await
+    }
+
+    pub(crate) async fn get<T>(
+        &self,
+        path: &str,
+        control: &RequestControl,
+    ) -> Result<OllamaResponse<T>, OllamaError> {
+        self.execute(Method::GET, path, None, control, false).await
+    }
+
+    /// Send a request to `path` through the interceptors, and through the cassette
+    /// if one is configured.
+    pub(crate) async fn execute<T>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+        control: &RequestControl,
+        streaming: bool,
+    ) -> Result<OllamaResponse<T>, OllamaError> {
+        let watchdog = control.start();
+        #[cfg(feature = "tracing")]
+        let started = tokio::time::Instant::now();
+        let mut request = InterceptedRequest {
+            method,
+            path: path.to_string(),
+            headers: HeaderMap::new(),
+            body,
+        };
+        self.interceptors.on_request(&mut request)?;
+        #[cfg(feature = "tracing")]
+        let span = telemetry::request_span(&request);
+
+        let send = async {
+            match &self.cassette {
+                Some(cassette) => cassette.execute(self, &request, &watchdog, streaming).await,
+                None => self.send_with_retry(&request, &watchdog, streaming).await,
+            }
+        };
+        #[cfg(feature = "tracing")]
+        let send = tracing::Instrument::instrument(send, span.clone());
+        let result = match send.await {
+            Ok(response) if !self.interceptors.is_empty() => {
+                self.interceptors.on_response(request.clone(), response)
+            }
+            result => result,
+        };
+        if let Err(e) = &result {
+            self.interceptors.on_error(&request, e);
+        }
+        #[cfg(feature = "tracing")]
+        telemetry::record_outcome(&span, started, &result);
+        let response = result?;
+
+        if streaming {
+            watchdog.touch();
+        }
+        let response = OllamaResponse::from(response).with_watchdog(watchdog);
+        #[cfg(feature = "tracing")]
+        let response = telemetry::trace_generation(path, span, response);
+        Ok(response)
+    }
+
+    /// Send a request to `path`, retrying transient failures according to the retry policy.
+    /// For streaming endpoints the first chunk is read before returning, so that
+    /// failures before any data is received are retried as well.
+    pub(crate) async fn send_with_retry(
+        &self,
+        request: &InterceptedRequest,
+        watchdog: &Watchdog,
+        streaming: bool,
+    ) -> Result<reqwest::Response, OllamaError> {
+        let url = self.url(&request.path);
+        let mut circuit = match &self.circuit_breaker {
+            Some(breaker) => Some(breaker.admit().ok_or_else(|| open_error(&self.host))?),
+            None => None,
+        };
+        let mut report = |success: bool| {
+            if let Some(circuit) = circuit.as_mut() {
+                circuit.report(success);
+            }
+        };
+        let mut attempt = 1;
+
+        loop {
+            let send = self.send(request, &url, streaming);
+            let failure = match watchdog.run(send).await {
+                Ok(Ok(response)) => {
+                    report(true);
+                    return Ok(response);
+                }
+                Ok(Err(failure)) => failure,
+                Err(e) => {
+                    if let OllamaError::Timeout(_) = e {
+                        report(false);
+                    }
+                    return Err(e);
+                }
+            };
+            #[cfg(feature = "tracing")]
+            telemetry::record_failure(&failure, attempt);
+
+            if !self.retry_policy.should_retry(&failure, attempt) {
+                report(!failure.is_server_failure());
+                return Err(failure.into());
+            }
+            let backoff = self.retry_policy.backoff(attempt);
+            watchdog.run(tokio::time::sleep(backoff)).await?;
+            attempt += 1;
+        }
+    }
+
+    async fn send(
+        &self,
+        request: &InterceptedRequest,
+        url: &str,
+        streaming: bool,
+    ) -> Result<reqwest::Response, Failure> {
+        let mut builder = self.http.request(request.method.clone(), url);
+        if let Some(api_key) = &self.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+        if !request.headers.is_empty() {
+            builder = builder.headers(request.headers.clone());
+        }
+        if let Some(body) = &request.body {
+            builder = builder.json(body);
+        }
+        let response = builder.send().await.map_err(Failure::Request)?;
+
+        if response.status() != StatusCode::OK {
+            let status = response.status();
+            let err_msg = response.text().await.unwrap_or_default();
+            return Err(Failure::Status(status, err_msg));
+        }
+
+        if !streaming {
+            return Ok(response);
+        }
+
+        let mut builder = http::Response::builder().status(response.status());
+        if let Some(headers) = builder.headers_mut() {
+            *headers = response.headers().clone();
+        }
+
+        let mut body = response.bytes_stream();
+        let first = match body.next().await {
+            Some(Ok(chunk)) => Some(Ok(chunk)),
+            Some(Err(e)) => return Err(Failure::Stream(e)),
+            None => None,
+        };
+
+        let body = reqwest::Body::wrap_stream(iter(first).chain(body));
+        Ok(builder.body(body).unwrap().into())
+    }
+}
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Instant};
+
+use super::OllamaClient;
+use crate::{control::RequestControl, errors::OllamaError};
+
+/// Interval between two heartbeats sent by [`OllamaClient::wait_until_ready`].
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionResponse {
+    /// The version of the Ollama server.
+    pub version: String,
+}
+
+impl OllamaClient {
+    /// Retrieve the Ollama version.
+    pub async fn version(&self) -> Result<VersionResponse, OllamaError> {
+        self.get("/api/version", &RequestControl::default())
+            .await?
+            .response()
+            .await
+    }
+
+    /// Send a heartbeat to the root endpoint, true if the server answered.
+    pub async fn is_alive(&self) -> bool {
+        match self.http.get(self.url("/")).send().await {
+            Ok(response) => response.status() == StatusCode::OK,
+            Err(_) => false,
+        }
+    }
+
+    /// Poll the server until it answers the heartbeat, or fail with
+    /// [`OllamaError::Timeout`] once `timeout` has elapsed.
+    pub async fn wait_until_ready(&self, timeout: Duration) -> Result<(), OllamaError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let heartbeat = tokio::time::timeout_at(deadline, self.is_alive()).await;
+            match heartbeat {
+                Ok(true) => return Ok(()),
+                Ok(false) if Instant::now() + READY_POLL_INTERVAL < deadline => {
+                    sleep(READY_POLL_INTERVAL).await
+                }
+                _ => {
+                    return Err(OllamaError::Timeout(format!(
+                        "server not ready within {timeout:?}"
+                    )))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::{fmt, sync::Arc};
+
+use async_stream::stream;
+use bytes::Bytes;
+use reqwest::{header::HeaderMap, Method};
+use tokio_stream::StreamExt;
+
+use crate::{
+    errors::OllamaError,
+    response::{response_from_stream, split_lines},
+};
+
+/// An outgoing request as seen by interceptors, before it is serialized.
+#[derive(Debug, Clone)]
+pub struct InterceptedRequest {
+    pub method: Method,
+
+    /// The path of the endpoint, e.g. `/api/chat`.
+    pub path: String,
+
+    /// Headers sent in addition to those of the client.
+    pub headers: HeaderMap,
+
+    /// The JSON body, if any.
+    pub body: Option<serde_json::Value>,
+}
+
+/// Hook into every request sent by a client, whatever the endpoint.
+///
+/// Requests go through the interceptors of a client in the order they were
+/// added, responses and their chunks in the reverse order. Every method does
+/// nothing by default.
+pub trait Interceptor: Send + Sync {
+    /// Inspect or modify `request` before it is sent, once per call whatever the
+    /// number of attempts. Returning an error aborts the call.
+    fn on_request(&self, request: &mut InterceptedRequest) -> Result<(), OllamaError> {
+        let _ = request;
+        Ok(())
+    }
+
+    /// Inspect the successful response to `request`, or modify its headers.
+    /// Returning an error fails the call.
+    fn on_response(
+        &self,
+        request: &InterceptedRequest,
+        response: &mut reqwest::Response,
+    ) -> Result<(), OllamaError> {
+        let _ = (request, response);
+        Ok(())
+    }
+
+    /// Inspect or modify a line of the body of the response to `request`, new
+    /// line included: each chunk of a streamed response, whatever the way it was
+    /// split in transit. A body which doesn't end with a new line is passed whole.
+    fn on_chunk(&self, request: &InterceptedRequest, chunk: &mut Bytes) {
+        let _ = (request, chunk);
+    }
+
+    /// Inspect the error `request` failed with.
+    fn on_error(&self, request: &InterceptedRequest, error: &OllamaError) {
+        let _ = (request, error);
+    }
+}
+
+/// The interceptors of a client.
+#[derive(Clone, Default)]
+pub(crate) struct InterceptorChain {
+    interceptors: Vec<Arc<dyn Interceptor>>,
+}
+
+impl InterceptorChain {
+    pub(crate) fn push(&mut self, interceptor: Arc<dyn Interceptor>) {
+        self.interceptors.push(interceptor);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.interceptors.is_empty()
+    }
+
+    pub(crate) fn on_request(&self, request: &mut InterceptedRequest) -> Result<(), OllamaError> {
+        self.interceptors
+            .iter()
+            .try_for_each(|interceptor| interceptor.on_request(request))
+    }
+
+    pub(crate) fn on_error(&self, request: &InterceptedRequest, error: &OllamaError) {
+        for interceptor in self.interceptors.iter().rev() {
+            interceptor.on_error(request, error);
+        }
+    }
+
+    /// Pass `response` through the response hooks, then the lines of its body
+    /// through the chunk hooks.
+    pub(crate) fn on_response(
+        &self,
+        request: InterceptedRequest,
+        mut response: reqwest::Response,
+    ) -> Result<reqwest::Response, OllamaError> {
+        for interceptor in self.interceptors.iter().rev() {
+            interceptor.on_response(&request, &mut response)?;
+        }
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let mut input = Box::pin(split_lines(response.bytes_stream()));
+        let interceptors = self.interceptors.clone();
+        let body = stream! {
+            while let Some(item) = input.next().await {
+                match item {
+                    Ok(mut chunk) => {
+                        for interceptor in interceptors.iter().rev() {
+                            interceptor.on_chunk(&request, &mut chunk);
+                        }
+                        yield Ok(chunk);
+                    }
+                    Err(e) => {
+                        yield Err(e);
+                        return;
+                    }
+                }
+            }
+        };
+
+        let mut intercepted = response_from_stream(status, body);
+        *intercepted.headers_mut() = headers;
+        Ok(intercepted)
+    }
+}
+
+impl fmt::Debug for InterceptorChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InterceptorChain")
+            .field("len", &self.interceptors.len())
+            .finish()
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    future::pending,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::sync::oneshot;
+
+use crate::{control::RequestControl, errors::OllamaError, model::canonical_name};
+
+/// Default maximum number of requests waiting for a slot.
+pub const DEFAULT_MAX_QUEUE: usize = 64;
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    in_flight: usize,
+    in_flight_per_model: HashMap<String, usize>,
+    queue: VecDeque<Waiter>,
+    next_waiter: u64,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    id: u64,
+    model: String,
+    admit: oneshot::Sender<()>,
+}
+
+/// Opt-in client policy limiting the number of chat, completion and embeddings
+/// requests in flight, in total and per model. Excess requests wait in a bounded
+/// FIFO queue; a request is rejected with [`OllamaError::QueueFull`] when the
+/// queue is full, and with [`OllamaError::Timeout`] when it waited longer than
+/// the queue timeout. A request holds its slot until its response has been read
+/// or dropped. Clones of a client share the slots of its limiter.
+#[derive(Clone)]
+pub struct ConcurrencyLimiter {
+    max_in_flight: usize,
+    max_in_flight_per_model: Option<usize>,
+    max_queue: usize,
+    queue_timeout: Option<Duration>,
+    state: Arc<Mutex<LimiterState>>,
+}
+
+impl ConcurrencyLimiter {
+    /// A limiter letting at most `max_in_flight` requests through at a time.
+    pub fn new(max_in_flight: usize) -> Self {
+        Self {
+            max_in_flight: max_in_flight.max(1),
+            max_in_flight_per_model: None,
+            max_queue: DEFAULT_MAX_QUEUE,
+            queue_timeout: None,
+            state: Arc::default(),
+        }
+    }
+
+    /// Let at most `max` requests for the same model through at a time.
+    pub fn max_in_flight_per_model(mut self, max: usize) -> Self {
+        self.max_in_flight_per_model = Some(max.max(1));
+        self
+    }
+
+    /// Let at most `max` requests wait for a slot, 0 rejecting every excess request.
+    pub fn max_queue(mut self, max: usize) -> Self {
+        self.max_queue = max;
+        self
+    }
+
+    /// Give up on requests which waited `timeout` for a slot.
+    pub fn queue_timeout(mut self, timeout: Duration) -> Self {
+        self.queue_timeout = Some(timeout);
+        self
+    }
+
+    /// The number of requests holding a slot.
+    pub fn in_flight(&self) -> usize {
+        self.state.lock().unwrap().in_flight
+    }
+
+    /// The number of requests for `model` holding a slot.
+    pub fn in_flight_for(&self, model: &str) -> usize {
+        let state = self.state.lock().unwrap();
+        let model = canonical_name(model);
+        state.in_flight_per_model.get(&model).copied().unwrap_or(0)
+    }
+
+    /// The number of requests waiting for a slot.
+    pub fn queue_depth(&self) -> usize {
+        self.state.lock().unwrap().queue.len()
+    }
+
+    /// The number of requests for `model` waiting for a slot.
+    pub fn queue_depth_for(&self, model: &str) -> usize {
+        let state = self.state.lock().unwrap();
+        let model = canonical_name(model);
+        state.queue.iter().filter(|w| w.model == model).count()
+    }
+
+    /// Wait for a slot for a request to `model`, until the queue timeout
+    /// elapses or the request is cancelled.
+    pub(crate) async fn acquire(
+        &self,
+        model: &str,
+        control: &RequestControl,
+    ) -> Result<Permit, OllamaError> {
+        let model = canonical_name(model);
+        let (id, admitted) = {
+            let mut state = self.state.lock().unwrap();
+            if self.fits(&state, &model) {
+                self.take_slot(&mut state, &model);
+                return Ok(self.permit(model));
+            }
+            if state.queue.len() >= self.max_queue {
+                return Err(OllamaError::QueueFull(format!(
+                    "{} requests already waiting",
+                    state.queue.len()
+                )));
+            }
+
+            let (admit, admitted) = oneshot::channel();
+            let id = state.next_waiter;
+            state.next_waiter += 1;
+            state.queue.push_back(Waiter {
+                id,
+                model: model.clone(),
+                admit,
+            });
+            (id, admitted)
+        };
+        let mut queued = Queued {
+            limiter: self.clone(),
+            id,
+            model: model.clone(),
+            done: false,
+        };
+
+        let cancelled = async {
+            match &control.cancellation {
+                Some(token) => token.cancelled().await,
+                None => pending().await,
+            }
+        };
+        let timed_out = async {
+            match self.queue_timeout {
+                Some(timeout) => tokio::time::sleep(timeout).await,
+                None => pending().await,
+            }
+        };
+
+        let err = tokio::select! {
+            _ = admitted => {
+                queued.done = true;
+                return Ok(self.permit(model));
+            }
+            _ = cancelled => OllamaError::Cancelled,
+            _ = timed_out => OllamaError::Timeout(format!(
+                "no slot available within {:?}",
+                self.queue_timeout.unwrap_or_default()
+            )),
+        };
+
+        // The request may have been admitted while giving up: keep the slot then.
+        match queued.leave() {
+            true => Err(err),
+            false => Ok(self.permit(model)),
+        }
+    }
+
+    fn fits(&self, state: &LimiterState, model: &str) -> bool {
+        let model_in_flight = state.in_flight_per_model.get(model).copied().unwrap_or(0);
+        let model_full = self
+            .max_in_flight_per_model
+            .is_some_and(|max| model_in_flight >= max);
+        state.in_flight < self.max_in_flight && !model_full
+    }
+
+    fn take_slot(&self, state: &mut LimiterState, model: &str) {
+        state.in_flight += 1;
+        *state
+            .in_flight_per_model
+            .entry(model.to_string())
+            .or_default() += 1;
+    }
+
+    fn release_slot(&self, state: &mut LimiterState, model: &str) {
+        state.in_flight -= 1;
+        if let Some(count) = state.in_flight_per_model.get_mut(model) {
+            *count -= 1;
+            if *count == 0 {
+                state.in_flight_per_model.remove(model);
+            }
+        }
+    }
+
+    /// Admit the queued requests which fit, in order. A request for a model at its
+    /// limit doesn't hold back the requests for other models queued after it.
+    fn admit_waiters(&self, state: &mut LimiterState) {
+        let mut index = 0;
+        while index < state.queue.len() && state.in_flight < self.max_in_flight {
+            if !self.fits(state, &state.queue[index].model) {
+                index += 1;
+                continue;
+            }
+            // A waiter which gave up in the meantime frees its slot when leaving.
+            let waiter = state.queue.remove(index).unwrap();
+            self.take_slot(state, &waiter.model);
+            let _ = waiter.admit.send(());
+        }
+    }
+
+    fn permit(&self, model: String) -> Permit {
+        Permit {
+            limiter: self.clone(),
+            model,
+        }
+    }
+}
+
+impl fmt::Debug for ConcurrencyLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConcurrencyLimiter")
+            .field("max_in_flight", &self.max_in_flight)
+            .field("max_in_flight_per_model", &self.max_in_flight_per_model)
+            .field("max_queue", &self.max_queue)
+            .field("queue_timeout", &self.queue_timeout)
+            .field("in_flight", &self.in_flight())
+            .field("queue_depth", &self.queue_depth())
+            .finish()
+    }
+}
+
+/// A slot held by a request, released when dropped.
+pub(crate) struct Permit {
+    limiter: ConcurrencyLimiter,
+    model: String,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut state = self.limiter.state.lock().unwrap();
+        self.limiter.release_slot(&mut state, &self.model);
+        self.limiter.admit_waiters(&mut state);
+    }
+}
+
+/// A request waiting in the queue of a [`ConcurrencyLimiter`]. Dropping it
+/// leaves the queue, or frees the slot if the request was admitted meanwhile.
+struct Queued {
+    limiter: ConcurrencyLimiter,
+    id: u64,
+    model: String,
+    done: bool,
+}
+
+impl Queued {
+    /// Leave the queue, false if the request has already been admitted.
+    fn leave(&mut self) -> bool {
+        self.done = true;
+        let mut state = self.limiter.state.lock().unwrap();
+        match state.queue.iter().position(|w| w.id == self.id) {
+            Some(position) => {
+                state.queue.remove(position);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Drop for Queued {
+    fn drop(&mut self) {
+        if !self.done && !self.leave() {
+            drop(self.limiter.permit(self.model.clone()));
+        }
+    }
+}
@@ -0,0 +1,22 @@
+pub mod auto_pull;
+mod batch;
+pub mod circuit_breaker;
+pub mod client;
+pub mod health;
+pub mod interceptor;
+pub mod limits;
+mod model;
+pub mod retry;
+#[cfg(feature = "tracing")]
+mod telemetry;
+
+// test module
+mod test_client;
+
+pub use auto_pull::AutoPull;
+pub use circuit_breaker::{CircuitBreaker, CircuitState};
+pub use client::{OllamaClient, OllamaClientBuilder, DEFAULT_HOST};
+pub use health::VersionResponse;
+pub use interceptor::{InterceptedRequest, Interceptor};
+pub use limits::ConcurrencyLimiter;
+pub use retry::{RetryPolicy, RetryPolicyBuilder};
@@ -0,0 +1,129 @@
+use reqwest::Method;
+
+use super::OllamaClient;
+use crate::{
+    control::RequestControl,
+    errors::OllamaError,
+    model::{
+        copy::CopyModelRequest,
+        create::{CreateModelRequest, CreateModelResponse},
+        delete::DeleteModelRequest,
+        generate_embeddings::{EmbeddingsRequest, EmbeddingsResponse},
+        list_local::ListLocalModelsResponse,
+        list_running::ListRunningModelsResponse,
+        pull::{PullModelRequest, PullModelResponse},
+        push::{PushModelRequest, PushModelResponse},
+        show_info::{ShowModelRequest, ShowModelResponse},
+    },
+    response::OllamaResponse,
+};
+
+impl OllamaClient {
+    /// Generate embeddings from a model.
+    /// See [`crate::model::generate_embeddings::generate_embeddings`].
+    pub async fn generate_embeddings(
+        &self,
+        request: EmbeddingsRequest,
+    ) -> Result<EmbeddingsResponse, OllamaError> {
+        let model = request.model.clone();
+        let _permit = self
+            .acquire_slot(&model, &RequestControl::default())
+            .await?;
+        self.with_auto_pull(&model, || async {
+            self.post(
+                "/api/embeddings",
+                &request,
+                &RequestControl::default(),
+                false,
+            )
+            .await?
+            .response()
+            .await
+        })
+        .await
+    }
+
+    /// Create a model from a Modelfile.
+    /// See [`crate::model::create::create`].
+    pub async fn create(
+        &self,
+        request: CreateModelRequest,
+    ) -> Result<OllamaResponse<CreateModelResponse>, OllamaError> {
+        self.post("/api/create", &request, &RequestControl::default(), true)
+            .await
+    }
+
+    /// List models that are available locally.
+    /// See [`crate::model::list_local::list_local`].
+    pub async fn list_local(&self) -> Result<ListLocalModelsResponse, OllamaError> {
+        self.get("/api/tags", &RequestControl::default())
+            .await?
+            .response()
+            .await
+    }
+
+    /// List models that are currently loaded into memory.
+    /// See [`crate::model::list_running::list_running`].
+    pub async fn list_running(&self) -> Result<ListRunningModelsResponse, OllamaError> {
+        self.get("/api/ps", &RequestControl::default())
+            .await?
+            .response()
+            .await
+    }
+
+    /// Show information about a model.
+    /// See [`crate::model::show_info::show_info`].
+    pub async fn show_info(
+        &self,
+        request: ShowModelRequest,
+    ) -> Result<ShowModelResponse, OllamaError> {
+        self.post("/api/show", &request, &RequestControl::default(), false)
+            .await?
+            .response()
+            .await
+    }
+
+    /// Copy a model.
+    /// See [`crate::model::copy::copy`].
+    pub async fn copy(&self, request: CopyModelRequest) -> Result<(), OllamaError> {
+        self.post::<()>("/api/copy", &request, &RequestControl::default(), false)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete a model and its data.
+    /// See [`crate::model::delete::delete`].
+    pub async fn delete(&self, request: DeleteModelRequest) -> Result<(), OllamaError> {
+        let body =
+            serde_json::to_value(&request).map_err(|e| OllamaError::ParseError(e.to_string()))?;
+        self.execute::<()>(
+            Method::DELETE,
+            "/api/delete",
+            Some(body),
+            &RequestControl::default(),
+            false,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Download a model from the ollama library.
+    /// See [`crate::model::pull::pull`].
+    pub async fn pull(
+        &self,
+        request: PullModelRequest,
+    ) -> Result<OllamaResponse<PullModelResponse>, OllamaError> {
+        self.post("/api/pull", &request, &RequestControl::default(), true)
+            .await
+    }
+
+    /// Upload a model to a model library.
+    /// See [`crate::model::push::push`].
+    pub async fn push(
+        &self,
+        request: PushModelRequest,
+    ) -> Result<OllamaResponse<PushModelResponse>, OllamaError> {
+        self.post("/api/push", &request, &RequestControl::default(), true)
+            .await
+    }
+}
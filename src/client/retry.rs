@@ -0,0 +1,137 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use derive_builder::Builder;
+use reqwest::StatusCode;
+
+use crate::errors::OllamaError;
+
+/// Retry policy applied to non-streaming calls and to streaming calls
+/// before the first chunk of the response is received.
+#[derive(Debug, Clone, PartialEq, Builder)]
+#[builder(default)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one.
+    /// (Default: 1, retries disabled)
+    pub max_attempts: u32,
+
+    /// Delay before the first retry.
+    /// (Default: 200ms)
+    pub initial_backoff: Duration,
+
+    /// Upper bound of the delay between two attempts.
+    /// (Default: 10s)
+    pub max_backoff: Duration,
+
+    /// Factor applied to the delay after each failed attempt.
+    /// (Default: 2.0)
+    pub multiplier: f64,
+
+    /// Fraction of the delay which is randomized, between 0 and 1.
+    /// (Default: 0.2)
+    pub jitter: f64,
+
+    /// HTTP status codes considered transient.
+    /// (Default: 429, 502, 503, 504)
+    pub retryable_statuses: Vec<u16>,
+
+    /// Retry when the connection cannot be established or times out.
+    /// (Default: true)
+    pub retry_connection_errors: bool,
+
+    /// Retry when a streaming response fails before its first chunk.
+    /// (Default: true)
+    pub retry_stream_errors: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.2,
+            retryable_statuses: vec![429, 502, 503, 504],
+            retry_connection_errors: true,
+            retry_stream_errors: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy with the default backoff making up to `max_attempts` attempts.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    /// The delay to wait after the failed attempt number `attempt` (starting at 1).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let base = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        let base = base.min(self.max_backoff.as_secs_f64());
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = 1.0 + jitter * (2.0 * random_unit() - 1.0);
+        Duration::from_secs_f64((base * factor).max(0.0))
+    }
+
+    pub(crate) fn should_retry(&self, failure: &Failure, attempt: u32) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+
+        match failure {
+            Failure::Request(e) => {
+                self.retry_connection_errors && (e.is_connect() || e.is_timeout())
+            }
+            Failure::Status(status, _) => self.retryable_statuses.contains(&status.as_u16()),
+            Failure::Stream(_) => self.retry_stream_errors,
+        }
+    }
+}
+
+/// The reason an attempt failed, before it is converted into an [`OllamaError`].
+#[derive(Debug)]
+pub(crate) enum Failure {
+    Request(reqwest::Error),
+    Status(StatusCode, String),
+    Stream(reqwest::Error),
+}
+
+impl Failure {
+    /// True if the failure is attributable to the server rather than to the request.
+    pub(crate) fn is_server_failure(&self) -> bool {
+        match self {
+            Failure::Request(_) | Failure::Stream(_) => true,
+            Failure::Status(status, _) => status.is_server_error(),
+        }
+    }
+}
+
+impl From<Failure> for OllamaError {
+    fn from(value: Failure) -> Self {
+        match value {
+            Failure::Request(e) => OllamaError::RequestError(e.to_string()),
+            Failure::Status(status, err_msg) => OllamaError::from_status(status, err_msg),
+            Failure::Stream(e) => OllamaError::StreamError(e.to_string()),
+        }
+    }
+}
+
+/// A pseudo-random number in [0, 1], good enough to spread retries.
+fn random_unit() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    hasher.write_u32(nanos);
+    hasher.finish() as f64 / u64::MAX as f64
+}
@@ -0,0 +1,142 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::time::Instant;
+use tokio_stream::StreamExt;
+use tracing::{field, Span};
+
+use super::{interceptor::InterceptedRequest, retry::Failure};
+use crate::{
+    chat_completion::response::ChatResponse,
+    completion::response::CompletionResponse,
+    errors::OllamaError,
+    response::{split_lines, OllamaResponse},
+};
+
+/// The span of an API call, with its status and latency recorded once the
+/// response starts.
+pub(crate) fn request_span(request: &InterceptedRequest) -> Span {
+    let model = request
+        .body
+        .as_ref()
+        .and_then(|body| body.get("model").or_else(|| body.get("name")))
+        .and_then(Value::as_str);
+    tracing::info_span!(
+        "ollama.request",
+        method = %request.method,
+        endpoint = %request.path,
+        model,
+        status = field::Empty,
+        latency_ms = field::Empty,
+        error = field::Empty,
+    )
+}
+
+/// Record the outcome of the call of `span` started at `started`.
+pub(crate) fn record_outcome(
+    span: &Span,
+    started: Instant,
+    result: &Result<reqwest::Response, OllamaError>,
+) {
+    span.record("latency_ms", started.elapsed().as_millis() as u64);
+    match result {
+        Ok(response) => {
+            span.record("status", response.status().as_u16());
+        }
+        Err(e) => {
+            span.record("error", field::display(e));
+        }
+    }
+}
+
+/// Record a failed attempt in the current span.
+pub(crate) fn record_failure(failure: &Failure, attempt: u32) {
+    if let Failure::Status(status, _) = failure {
+        Span::current().record("status", status.as_u16());
+    }
+    tracing::debug!(attempt, failure = ?failure, "attempt failed");
+}
+
+/// The metrics of the final chunk of a generation.
+struct Metrics {
+    model: String,
+    prompt_eval_count: Option<usize>,
+    eval_count: Option<usize>,
+    load_duration: Option<usize>,
+    total_duration: Option<usize>,
+    tokens_per_second: Option<f64>,
+}
+
+trait Generation: DeserializeOwned {
+    /// The metrics of the chunk, if final.
+    fn metrics(self) -> Option<Metrics>;
+}
+
+impl Generation for ChatResponse {
+    fn metrics(self) -> Option<Metrics> {
+        self.done.then(|| Metrics {
+            tokens_per_second: self.tokens_per_second(),
+            model: self.model,
+            prompt_eval_count: self.prompt_eval_count,
+            eval_count: self.eval_count,
+            load_duration: self.load_duration,
+            total_duration: self.total_duration,
+        })
+    }
+}
+
+impl Generation for CompletionResponse {
+    fn metrics(self) -> Option<Metrics> {
+        self.done.then(|| Metrics {
+            tokens_per_second: self.tokens_per_second(),
+            model: self.model,
+            prompt_eval_count: self.prompt_eval_count,
+            eval_count: self.eval_count,
+            load_duration: self.load_duration,
+            total_duration: self.total_duration,
+        })
+    }
+}
+
+/// Emit an event in `span` with the metrics of the final chunk of the response,
+/// for the chat and completion endpoints.
+pub(crate) fn trace_generation<T>(
+    path: &str,
+    span: Span,
+    response: OllamaResponse<T>,
+) -> OllamaResponse<T> {
+    match path {
+        "/api/chat" => tap::<ChatResponse, T>(span, response),
+        "/api/generate" => tap::<CompletionResponse, T>(span, response),
+        _ => response,
+    }
+}
+
+fn tap<G: Generation, T>(span: Span, response: OllamaResponse<T>) -> OllamaResponse<T> {
+    response.map_body(move |input| {
+        split_lines(input).map(move |item| {
+            if let Ok(line) = &item {
+                report::<G>(&span, line);
+            }
+            item
+        })
+    })
+}
+
+fn report<G: Generation>(span: &Span, line: &[u8]) {
+    let Some(metrics) = serde_json::from_slice::<G>(line)
+        .ok()
+        .and_then(Generation::metrics)
+    else {
+        return;
+    };
+    tracing::info!(
+        parent: span,
+        model = %metrics.model,
+        prompt_eval_count = metrics.prompt_eval_count.map(|n| n as u64),
+        eval_count = metrics.eval_count.map(|n| n as u64),
+        load_duration = metrics.load_duration.map(|n| n as u64),
+        total_duration = metrics.total_duration.map(|n| n as u64),
+        tokens_per_second = metrics.tokens_per_second,
+        "generation completed"
+    );
+}
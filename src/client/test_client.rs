@@ -0,0 +1,700 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use bytes::Bytes;
+    use futures_util::StreamExt;
+    use reqwest::header::HeaderValue;
+    use serde_json::json;
+
+    use crate::{
+        chat_completion::request::ChatCompletionRequestBuilder,
+        client::{
+            AutoPull, CircuitBreaker, CircuitState, ConcurrencyLimiter, InterceptedRequest,
+            Interceptor, OllamaClient, OllamaClientBuilder, RetryPolicyBuilder, VersionResponse,
+            DEFAULT_HOST,
+        },
+        control::RequestControlBuilder,
+        errors::OllamaError,
+        testing::{MockOllama, MockResponse},
+    };
+
+    fn chat_request(model: &str) -> crate::chat_completion::request::ChatCompletionRequest {
+        ChatCompletionRequestBuilder::default()
+            .model(model)
+            .messages(vec![])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_client_url() {
+        let client = OllamaClient::default();
+        assert_eq!(client.host(), DEFAULT_HOST);
+        assert_eq!(client.url("/api/chat"), "http://localhost:11434/api/chat");
+
+        let client = OllamaClient::new("http://ollama:11434/");
+        assert_eq!(
+            client.url("/api/generate"),
+            "http://ollama:11434/api/generate"
+        );
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let policy = RetryPolicyBuilder::default()
+            .max_attempts(5)
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(300))
+            .jitter(0.0)
+            .build()
+            .unwrap();
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(300));
+        assert_eq!(policy.backoff(10), Duration::from_millis(300));
+
+        let policy = RetryPolicyBuilder::default()
+            .initial_backoff(Duration::from_millis(100))
+            .jitter(0.5)
+            .build()
+            .unwrap();
+        for _ in 0..20 {
+            let backoff = policy.backoff(1);
+            assert!(backoff >= Duration::from_millis(50) && backoff <= Duration::from_millis(150));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_connection_refused() {
+        let policy = RetryPolicyBuilder::default()
+            .max_attempts(3)
+            .initial_backoff(Duration::from_millis(10))
+            .build()
+            .unwrap();
+        let client = OllamaClientBuilder::default()
+            .host("http://127.0.0.1:1")
+            .retry_policy(policy)
+            .build()
+            .unwrap();
+
+        let started = std::time::Instant::now();
+        let result = client
+            .post::<()>("/api/chat", &"{}", &Default::default(), true)
+            .await;
+        assert!(result.is_err());
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_version_response() {
+        let version: VersionResponse = serde_json::from_str("{\"version\":\"0.1.48\"}").unwrap();
+        assert_eq!(version.version, "0.1.48");
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready_timeout() {
+        let client = OllamaClient::new("http://127.0.0.1:1");
+        assert!(!client.is_alive().await);
+
+        let result = client.wait_until_ready(Duration::from_millis(300)).await;
+        assert!(matches!(result, Err(OllamaError::Timeout(_))));
+    }
+
+    #[test]
+    fn test_model_not_found() {
+        let err_msg =
+            String::from("{\"error\":\"model 'unknown model' not found, try pulling it first\"}");
+        assert!(OllamaError::OllamaError(err_msg).is_model_not_found());
+        assert!(!OllamaError::OllamaError(String::from("{}")).is_model_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_retry_service_unavailable() {
+        let mock = MockOllama::start().await;
+        mock.respond("/api/chat", MockResponse::error(503, "loading model"));
+        mock.respond("/api/chat", MockResponse::error(503, "loading model"));
+
+        let policy = RetryPolicyBuilder::default()
+            .max_attempts(3)
+            .initial_backoff(Duration::from_millis(10))
+            .build()
+            .unwrap();
+        let client = OllamaClientBuilder::default()
+            .host(mock.url())
+            .retry_policy(policy)
+            .build()
+            .unwrap();
+
+        let response = client.chat(chat_request("llama3")).await.unwrap();
+        response.as_response().await.unwrap();
+        assert_eq!(mock.requests_to("/api/chat").len(), 3);
+
+        mock.respond("/api/chat", MockResponse::error(400, "bad request"));
+        assert!(client.chat(chat_request("llama3")).await.is_err());
+        assert_eq!(mock.requests_to("/api/chat").len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_auto_pull() {
+        let mock = MockOllama::start().await;
+        let not_found = "model 'llama3' not found, try pulling it first";
+        mock.respond("/api/chat", MockResponse::error(404, not_found));
+
+        let progress = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let recorded = progress.clone();
+        let auto_pull = AutoPull::new().on_progress(move |update| {
+            recorded.lock().unwrap().push(update.status.clone());
+        });
+        let client = OllamaClientBuilder::default()
+            .host(mock.url())
+            .auto_pull(auto_pull)
+            .build()
+            .unwrap();
+
+        let response = client.chat(chat_request("llama3")).await.unwrap();
+        response.as_response().await.unwrap();
+
+        let paths: Vec<String> = mock.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(paths, vec!["/api/chat", "/api/pull", "/api/chat"]);
+        assert_eq!(progress.lock().unwrap().last().unwrap(), "success");
+
+        mock.respond("/api/chat", MockResponse::error(404, not_found));
+        let err = mock
+            .client()
+            .chat(chat_request("llama3"))
+            .await
+            .err()
+            .unwrap();
+        assert!(err.is_model_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker() {
+        let mock = MockOllama::start().await;
+        let changes = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let recorded = changes.clone();
+        let breaker = CircuitBreaker::new()
+            .failure_threshold(2)
+            .open_duration(Duration::from_millis(100))
+            .on_state_change(move |state| recorded.lock().unwrap().push(state));
+        let client = OllamaClientBuilder::default()
+            .host(mock.url())
+            .circuit_breaker(breaker.clone())
+            .build()
+            .unwrap();
+
+        // Client errors don't count as failures.
+        mock.respond("/api/chat", MockResponse::error(400, "bad request"));
+        mock.respond("/api/chat", MockResponse::error(500, "out of memory"));
+        mock.respond("/api/chat", MockResponse::error(500, "out of memory"));
+        for _ in 0..3 {
+            assert!(client.chat(chat_request("llama3")).await.is_err());
+        }
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert_eq!(breaker.consecutive_failures(), 2);
+
+        let err = client.chat(chat_request("llama3")).await.err().unwrap();
+        assert!(matches!(err, OllamaError::CircuitOpen(_)));
+        assert_eq!(mock.requests_to("/api/chat").len(), 3);
+
+        // A failed probe opens the circuit again, a successful one closes it.
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        mock.respond("/api/chat", MockResponse::error(503, "loading"));
+        assert!(client.chat(chat_request("llama3")).await.is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        let response = client.chat(chat_request("llama3")).await.unwrap();
+        response.as_response().await.unwrap();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(breaker.consecutive_failures(), 0);
+
+        use CircuitState::*;
+        let changes = changes.lock().unwrap().clone();
+        assert_eq!(changes, vec![Open, HalfOpen, Open, HalfOpen, Closed]);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_connection_refused() {
+        let client = OllamaClientBuilder::default()
+            .host("http://127.0.0.1:1")
+            .circuit_breaker(CircuitBreaker::new().failure_threshold(1))
+            .build()
+            .unwrap();
+
+        let err = client.chat(chat_request("llama3")).await.err().unwrap();
+        assert!(matches!(err, OllamaError::RequestError(_)));
+        let err = client
+            .clone()
+            .chat(chat_request("llama3"))
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, OllamaError::CircuitOpen(_)));
+        assert_eq!(
+            client.circuit_breaker().map(CircuitBreaker::state),
+            Some(CircuitState::Open)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limiter_queue() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["a", "b"]).with_delay(Duration::from_millis(50)),
+        );
+        let limiter = ConcurrencyLimiter::new(1).max_queue(1);
+        let client = OllamaClientBuilder::default()
+            .host(mock.url())
+            .concurrency_limiter(limiter.clone())
+            .build()
+            .unwrap();
+
+        let first = client.chat(chat_request("llama3")).await.unwrap();
+        assert_eq!(limiter.in_flight(), 1);
+
+        let queued = tokio::spawn({
+            let client = client.clone();
+            async move { client.chat(chat_request("llama3")).await.map(|_| ()) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(limiter.queue_depth(), 1);
+        assert_eq!(limiter.queue_depth_for("llama3:latest"), 1);
+
+        let err = client.chat(chat_request("llama3")).await.err().unwrap();
+        assert!(matches!(err, OllamaError::QueueFull(_)));
+
+        // The slot is released once the response has been read.
+        first.as_response().await.unwrap();
+        queued.await.unwrap().unwrap();
+        assert_eq!(limiter.queue_depth(), 0);
+        assert_eq!(limiter.in_flight(), 0);
+        assert_eq!(mock.requests_to("/api/chat").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limiter_per_model() {
+        let mock = MockOllama::start().await;
+        let limiter = ConcurrencyLimiter::new(2)
+            .max_in_flight_per_model(1)
+            .queue_timeout(Duration::from_millis(50));
+        let client = OllamaClientBuilder::default()
+            .host(mock.url())
+            .concurrency_limiter(limiter.clone())
+            .build()
+            .unwrap();
+
+        let _llama = client.chat(chat_request("llama3")).await.unwrap();
+        let _phi = client.chat(chat_request("phi3")).await.unwrap();
+        assert_eq!(limiter.in_flight_for("llama3"), 1);
+        assert_eq!(limiter.in_flight(), 2);
+
+        let err = client.chat(chat_request("llama3")).await.err().unwrap();
+        assert!(matches!(err, OllamaError::Timeout(_)));
+        assert_eq!(limiter.queue_depth(), 0);
+
+        drop(_phi);
+        let control = RequestControlBuilder::default()
+            .cancellation(crate::control::CancellationToken::new())
+            .build()
+            .unwrap();
+        control.cancellation.as_ref().unwrap().cancel();
+        let err = client
+            .chat_with_control(chat_request("llama3"), control)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err, OllamaError::Cancelled);
+        assert!(client.chat(chat_request("mistral")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_chat_batch() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("a", &["slow"]).with_delay(Duration::from_millis(50)),
+        );
+        let client = mock.client();
+        let requests = vec![chat_request("a"), chat_request("b"), chat_request("c")];
+
+        let results = client.chat_batch(requests.clone(), 2).await;
+        let models: Vec<_> = results.into_iter().map(|r| r.unwrap().model).collect();
+        assert_eq!(models, vec!["a", "b", "c"]);
+
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("a", &["slow"]).with_delay(Duration::from_millis(50)),
+        );
+        let completed: Vec<usize> = client
+            .chat_batch_stream(requests, 2)
+            .map(|(index, result)| {
+                assert!(result.is_ok());
+                index
+            })
+            .collect()
+            .await;
+        assert_eq!(completed, vec![1, 2, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_completion_batch_errors() {
+        let mock = MockOllama::start().await;
+        let client = mock.client();
+        let request = |model: &str| crate::completion::request::CompletionRequest {
+            model: model.to_string(),
+            prompt: String::from("Hi"),
+            ..Default::default()
+        };
+        let requests: Vec<_> = ["a", "b", "c", "d"].into_iter().map(request).collect();
+
+        mock.respond(
+            "/api/generate",
+            MockResponse::completion_stream("a", &["ok"], vec![]),
+        );
+        mock.respond("/api/generate", MockResponse::error(500, "out of memory"));
+        let results = client.completion_batch(requests.clone(), 1).await;
+        assert!(results[0].is_ok() && results[2].is_ok() && results[3].is_ok());
+        assert!(matches!(&results[1], Err(OllamaError::ServerError(_))));
+        assert_eq!(mock.requests_to("/api/generate").len(), 4);
+
+        mock.respond(
+            "/api/generate",
+            MockResponse::completion_stream("a", &["ok"], vec![]),
+        );
+        mock.respond("/api/generate", MockResponse::error(500, "out of memory"));
+        let err = client.try_completion_batch(requests.clone(), 1).await;
+        assert!(err.is_err());
+        assert_eq!(mock.requests_to("/api/generate").len(), 6);
+
+        let responses = client.try_completion_batch(requests, 3).await.unwrap();
+        let models: Vec<_> = responses.into_iter().map(|r| r.model).collect();
+        assert_eq!(models, vec!["a", "b", "c", "d"]);
+    }
+
+    #[tokio::test]
+    async fn test_idle_timeout_mock() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["a", "b"]).with_delay(Duration::from_millis(200)),
+        );
+
+        let control = RequestControlBuilder::default()
+            .idle_timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let response = mock
+            .client()
+            .chat_with_control(chat_request("llama3"), control)
+            .await
+            .unwrap();
+        let err = response.as_response().await.err().unwrap();
+        assert!(matches!(err, OllamaError::Timeout(_)));
+    }
+
+    /// Records the hooks it runs as `"{name} {hook}"`.
+    struct Recorder {
+        name: &'static str,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Recorder {
+        fn record(&self, hook: &str) {
+            let event = format!("{} {hook}", self.name);
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    impl Interceptor for Recorder {
+        fn on_request(&self, request: &mut InterceptedRequest) -> Result<(), OllamaError> {
+            self.record("request");
+            request
+                .headers
+                .insert("x-request-id", HeaderValue::from_static("42"));
+            if let Some(body) = request.body.as_mut() {
+                body["options"] = json!({ "temperature": 0.0 });
+            }
+            Ok(())
+        }
+
+        fn on_response(
+            &self,
+            _request: &InterceptedRequest,
+            _response: &mut reqwest::Response,
+        ) -> Result<(), OllamaError> {
+            self.record("response");
+            Ok(())
+        }
+
+        fn on_chunk(&self, _request: &InterceptedRequest, chunk: &mut Bytes) {
+            let text = String::from_utf8_lossy(chunk).replace("Hello", "Howdy");
+            *chunk = Bytes::from(text);
+        }
+
+        fn on_error(&self, _request: &InterceptedRequest, _error: &OllamaError) {
+            self.record("error");
+        }
+    }
+
+    /// Rejects every request.
+    struct Deny;
+
+    impl Interceptor for Deny {
+        fn on_request(&self, request: &mut InterceptedRequest) -> Result<(), OllamaError> {
+            Err(OllamaError::InvalidParameter(format!(
+                "{} is denied",
+                request.path
+            )))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interceptors() {
+        let mock = MockOllama::start().await;
+        let events = Arc::new(Mutex::new(vec![]));
+        let client = OllamaClientBuilder::default()
+            .host(mock.url())
+            .interceptor(Recorder {
+                name: "first",
+                events: events.clone(),
+            })
+            .interceptor(Recorder {
+                name: "second",
+                events: events.clone(),
+            })
+            .build()
+            .unwrap();
+
+        let response = client.chat(chat_request("llama3")).await.unwrap();
+        let message = response.as_response().await.unwrap().message.unwrap();
+        assert_eq!(message.content, "Howdy from mock");
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "first request",
+                "second request",
+                "second response",
+                "first response"
+            ]
+        );
+
+        let request = &mock.requests_to("/api/chat")[0];
+        assert_eq!(request.headers["x-request-id"], "42");
+        assert_eq!(
+            request.body.as_ref().unwrap()["options"]["temperature"],
+            0.0
+        );
+
+        // Non-generation endpoints go through the chain as well.
+        events.lock().unwrap().clear();
+        mock.respond("/api/tags", MockResponse::error(500, "boom"));
+        assert!(client.list_local().await.is_err());
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "first request",
+                "second request",
+                "second error",
+                "first error"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_interceptor_chunks_are_lines() {
+        let mock = MockOllama::start().await;
+        let line = json!({
+            "model": "llama3",
+            "created_at": "2024-06-01T00:00:00Z",
+            "message": { "role": "assistant", "content": "Hello from mock" },
+            "done": true,
+        });
+        let body = format!("{line}\n");
+        // Split "Hello" between two chunks.
+        let split = body.find("llo").unwrap();
+        let body = body.into_bytes();
+        mock.respond(
+            "/api/chat",
+            MockResponse::chunks(vec![body[..split].to_vec(), body[split..].to_vec()])
+                .with_delay(Duration::from_millis(20)),
+        );
+        let client = OllamaClientBuilder::default()
+            .host(mock.url())
+            .interceptor(Recorder {
+                name: "recorder",
+                events: Arc::default(),
+            })
+            .build()
+            .unwrap();
+
+        let response = client.chat(chat_request("llama3")).await.unwrap();
+        let message = response.as_response().await.unwrap().message.unwrap();
+        assert_eq!(message.content, "Howdy from mock");
+    }
+
+    #[tokio::test]
+    async fn test_interceptor_rejects_request() {
+        let mock = MockOllama::start().await;
+        let events = Arc::new(Mutex::new(vec![]));
+        let client = OllamaClientBuilder::default()
+            .host(mock.url())
+            .interceptor(Deny)
+            .interceptor(Recorder {
+                name: "recorder",
+                events: events.clone(),
+            })
+            .build()
+            .unwrap();
+
+        let err = client.chat(chat_request("llama3")).await.err().unwrap();
+        assert!(matches!(err, OllamaError::InvalidParameter(_)));
+        assert!(mock.requests().is_empty());
+        assert!(events.lock().unwrap().is_empty());
+    }
+
+    /// Collects the fields of every span and event.
+    #[cfg(feature = "tracing")]
+    #[derive(Clone, Default)]
+    struct Collector {
+        spans: Arc<Mutex<Vec<Fields>>>,
+        events: Arc<Mutex<Vec<Fields>>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    type Fields = std::collections::HashMap<String, String>;
+
+    /// Index of a span in [`Collector::spans`].
+    #[cfg(feature = "tracing")]
+    struct SpanIndex(usize);
+
+    #[cfg(feature = "tracing")]
+    struct Visitor<'a>(&'a mut Fields);
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for Visitor<'_> {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl<S> tracing_subscriber::Layer<S> for Collector
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            id: &tracing::span::Id,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = Fields::new();
+            attrs.record(&mut Visitor(&mut fields));
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(fields);
+            let span = ctx.span(id).unwrap();
+            span.extensions_mut().insert(SpanIndex(spans.len() - 1));
+        }
+
+        fn on_record(
+            &self,
+            id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let span = ctx.span(id).unwrap();
+            let extensions = span.extensions();
+            let SpanIndex(index) = extensions.get::<SpanIndex>().unwrap();
+            values.record(&mut Visitor(&mut self.spans.lock().unwrap()[*index]));
+        }
+
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = Fields::new();
+            event.record(&mut Visitor(&mut fields));
+            self.events.lock().unwrap().push(fields);
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn test_tracing() {
+        let collector = Collector::default();
+        let subscriber = tracing_subscriber::layer::SubscriberExt::with(
+            tracing_subscriber::registry(),
+            collector.clone(),
+        );
+        let _guard = tracing::subscriber::set_default(subscriber);
+        let mock = MockOllama::start().await;
+        let client = mock.client();
+
+        let response = client.chat(chat_request("llama3")).await.unwrap();
+        response.as_response().await.unwrap();
+        {
+            let spans = collector.spans.lock().unwrap();
+            assert_eq!(spans[0]["endpoint"], "/api/chat");
+            assert_eq!(spans[0]["model"], "llama3");
+            assert_eq!(spans[0]["status"], "200");
+            assert!(spans[0].contains_key("latency_ms"));
+
+            let events = collector.events.lock().unwrap();
+            let generation = events
+                .iter()
+                .find(|event| event["message"] == "generation completed")
+                .unwrap();
+            assert_eq!(generation["prompt_eval_count"], "10");
+            assert_eq!(generation["eval_count"], "3");
+            assert_eq!(generation["load_duration"], "1000000");
+            assert_eq!(generation["tokens_per_second"], "1500.0");
+        }
+
+        mock.respond("/api/tags", MockResponse::error(500, "boom"));
+        assert!(client.list_local().await.is_err());
+        let spans = collector.spans.lock().unwrap();
+        assert_eq!(spans[1]["endpoint"], "/api/tags");
+        assert_eq!(spans[1]["status"], "500");
+        assert!(spans[1]["error"].contains("boom"));
+    }
+
+    #[tokio::test]
+    async fn test_health_mock() {
+        let mock = MockOllama::start().await;
+        let client = mock.client();
+        client
+            .wait_until_ready(Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(client.is_alive().await);
+        assert_eq!(client.version().await.unwrap().version, "0.0.0-mock");
+    }
+
+    #[ignore]
+    #[tokio::test]
+    async fn test_version() {
+        let client = OllamaClient::default();
+        client
+            .wait_until_ready(Duration::from_secs(5))
+            .await
+            .unwrap();
+        let version = client.version().await.unwrap().version;
+        // e.g. "0.1.48", or "0.0.0" for a development build.
+        let parts: Vec<&str> = version.split(['.', '-']).collect();
+        assert!(parts.len() >= 3, "unexpected version: {version}");
+        assert!(parts[..3].iter().all(|part| part.parse::<u32>().is_ok()));
+    }
+}
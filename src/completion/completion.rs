@@ -1,7 +1,6 @@
 use super::{request::CompletionRequest, response::CompletionResponse};
 use crate::{
-    errors::OllamaError,
-    response::{check_response_valid, OllamaResponse},
+    client::OllamaClient, control::RequestControl, errors::OllamaError, response::OllamaResponse,
 };
 
 /// Generate a response for a given prompt with a provided model. This is a streaming endpoint,
@@ -10,13 +9,16 @@ use crate::{
 pub async fn completion(
     request: CompletionRequest,
 ) -> Result<OllamaResponse<CompletionResponse>, OllamaError> {
-    let url = format!("http://localhost:11434/api/generate");
-    let resp = reqwest::Client::default()
-        .post(url)
-        .json(&request)
-        .send()
-        .await;
+    completion_with_control(request, RequestControl::default()).await
+}
 
-    let response = check_response_valid(resp).await?;
-    Ok(response.into())
+/// Same as [`completion`], aborting the request with [`OllamaError::Cancelled`] or
+/// [`OllamaError::Timeout`] according to `control`.
+pub async fn completion_with_control(
+    request: CompletionRequest,
+    control: RequestControl,
+) -> Result<OllamaResponse<CompletionResponse>, OllamaError> {
+    OllamaClient::default()
+        .completion_with_control(request, control)
+        .await
 }
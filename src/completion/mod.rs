@@ -1,6 +1,7 @@
 pub mod completion;
 pub mod request;
 pub mod response;
+pub mod session;
 
 // test module
 mod test_completion;
@@ -1,5 +1,5 @@
 use derive_builder::Builder;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     format::Format,
@@ -9,7 +9,7 @@ use crate::{
 /// Ollama API Doc
 /// Modelfile: https://github.com/ollama/ollama/blob/main/docs/modelfile.md#valid-parameters-and-values
 
-#[derive(Debug, Clone, Default, Builder, Serialize)]
+#[derive(Debug, Clone, Default, Builder, Serialize, Deserialize)]
 pub struct CompletionRequest {
     /// Required parameters
 
@@ -23,7 +23,7 @@ pub struct CompletionRequest {
 
     /// A a list of base64-encoded images (for multimodal models such as llava).
     #[builder(setter(strip_option), default)]
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub images: Vec<String>,
 
     /// Advanced parameters (optional)
@@ -40,7 +40,7 @@ pub struct CompletionRequest {
         ty = "crate::options::OptionsBuilder",
         build = r#"self.options.build().unwrap()"#
     ))]
-    #[serde(skip_serializing_if = "crate::options::Options::is_default")]
+    #[serde(default, skip_serializing_if = "crate::options::Options::is_default")]
     pub options: Options,
 
     /// System message to (overrides what is defined in the Modelfile).
@@ -18,6 +18,10 @@ pub struct CompletionResponse {
 
     pub done: bool,
 
+    /// Why generation stopped, set on the final chunk: `stop`, or `length`
+    /// once `num_predict` tokens were generated.
+    pub done_reason: Option<String>,
+
     /// Empty if the response was streamed,
     /// if not streamed, this will contain the full response.
     pub response: String,
@@ -45,6 +49,19 @@ pub struct CompletionResponse {
     pub context: Option<Vec<usize>>,
 }
 
+impl CompletionResponse {
+    /// Generation speed in tokens per second, computed from `eval_count` and
+    /// `eval_duration` which are only set on the final chunk.
+    pub fn tokens_per_second(&self) -> Option<f64> {
+        match (self.eval_count, self.eval_duration) {
+            (Some(count), Some(duration)) if duration > 0 => {
+                Some(count as f64 / duration as f64 * 1e9)
+            }
+            _ => None,
+        }
+    }
+}
+
 #[async_trait]
 impl StreamHandler for CompletionResponse {
     async fn adapt_stream(
@@ -0,0 +1,123 @@
+use std::sync::{Arc, Mutex};
+
+use async_stream::stream;
+use tokio_stream::StreamExt;
+
+use super::{request::CompletionRequest, response::CompletionResponse};
+use crate::{
+    client::OllamaClient, conversation::Conversation, errors::OllamaError, response::OllamaStream,
+};
+
+/// Keeps a short conversational memory on `/api/generate` by feeding the `context`
+/// returned with each response into the following request.
+#[derive(Debug)]
+pub struct CompletionSession {
+    client: OllamaClient,
+    template: CompletionRequest,
+    context: Arc<Mutex<Option<Vec<usize>>>>,
+}
+
+impl CompletionSession {
+    /// Create a session from a request template. Every prompt is sent with the model,
+    /// options, system message and template of `template`, the initial context is
+    /// taken from `template.context`.
+    pub fn new(mut template: CompletionRequest) -> Self {
+        let context = template.context.take();
+        Self {
+            client: OllamaClient::default(),
+            template,
+            context: Arc::new(Mutex::new(context)),
+        }
+    }
+
+    /// Send the prompts of this session with `client`.
+    pub fn with_client(mut self, client: OllamaClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// The context that will be sent with the next prompt.
+    pub fn context(&self) -> Option<Vec<usize>> {
+        self.context.lock().unwrap().clone()
+    }
+
+    /// Forget the conversational memory, the next prompt starts a new conversation.
+    pub fn reset(&mut self) {
+        *self.context.lock().unwrap() = None;
+    }
+
+    /// Create an independent session continuing from the current context.
+    pub fn fork(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            template: self.template.clone(),
+            context: Arc::new(Mutex::new(self.context())),
+        }
+    }
+
+    /// Store the context of `response`, if any, for the next prompt.
+    pub fn record(&self, response: &CompletionResponse) {
+        record_context(&self.context, response);
+    }
+
+    /// Build the request for `prompt` chained to the current context.
+    pub fn request(&self, prompt: impl Into<String>) -> CompletionRequest {
+        CompletionRequest {
+            prompt: prompt.into(),
+            context: self.context(),
+            ..self.template.clone()
+        }
+    }
+
+    /// Generate the full response for `prompt` and keep its context.
+    pub async fn complete(
+        &self,
+        prompt: impl Into<String>,
+    ) -> Result<CompletionResponse, OllamaError> {
+        let response = self
+            .client
+            .completion(self.request(prompt))
+            .await?
+            .as_response()
+            .await?;
+        self.record(&response);
+        Ok(response)
+    }
+
+    /// Stream the response for `prompt`. The context carried by the final
+    /// chunk is stored once the stream reaches it.
+    pub async fn stream(
+        &self,
+        prompt: impl Into<String>,
+    ) -> Result<OllamaStream<CompletionResponse>, OllamaError> {
+        let mut input = self
+            .client
+            .completion(self.request(prompt))
+            .await?
+            .as_stream()
+            .await?;
+        let context = self.context.clone();
+        let recorded = stream! {
+            while let Some(item) = input.next().await {
+                if let Ok(inner) = &item {
+                    record_context(&context, inner);
+                }
+                yield item;
+            }
+        };
+
+        Ok(Box::pin(recorded))
+    }
+}
+
+impl From<&Conversation> for CompletionSession {
+    fn from(value: &Conversation) -> Self {
+        Self::new(value.completion_request(""))
+    }
+}
+
+fn record_context(context: &Mutex<Option<Vec<usize>>>, response: &CompletionResponse) {
+    if response.context.is_some() {
+        *context.lock().unwrap() = response.context.clone();
+    }
+}
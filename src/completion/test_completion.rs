@@ -4,9 +4,13 @@ mod tests {
     use tokio_stream::StreamExt;
 
     use crate::{
-        completion::{completion::completion, request::CompletionRequestBuilder},
+        completion::{
+            completion::completion, request::CompletionRequestBuilder,
+            response::CompletionResponse, session::CompletionSession,
+        },
         errors::OllamaError,
         options::OptionsConstructor,
+        testing::{MockOllama, MockResponse},
     };
 
     #[test]
@@ -22,6 +26,82 @@ mod tests {
         println!("serialized: {}", serialized);
     }
 
+    #[test]
+    fn test_session_context() {
+        let template = CompletionRequestBuilder::default()
+            .model("llama3")
+            .prompt("")
+            .context(vec![1])
+            .build()
+            .unwrap();
+
+        let mut session = CompletionSession::new(template);
+        assert_eq!(session.request("hello").context, Some(vec![1]));
+
+        session.record(&CompletionResponse {
+            context: Some(vec![1, 2, 3]),
+            ..Default::default()
+        });
+        let forked = session.fork();
+        assert_eq!(session.request("again").context, Some(vec![1, 2, 3]));
+
+        session.reset();
+        assert_eq!(session.request("again").context, None);
+        assert_eq!(forked.context(), Some(vec![1, 2, 3]));
+    }
+
+    #[tokio::test]
+    async fn test_session_mock() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/generate",
+            MockResponse::completion_stream("llama3", &["Hi"], vec![4, 5]),
+        );
+        mock.respond(
+            "/api/generate",
+            MockResponse::completion_stream("llama3", &["Again"], vec![4, 5, 6]),
+        );
+
+        let template = CompletionRequestBuilder::default()
+            .model("llama3")
+            .prompt("")
+            .build()
+            .unwrap();
+        let session = CompletionSession::new(template).with_client(mock.client());
+
+        let response = session.complete("Hello").await.unwrap();
+        assert_eq!(response.response, "Hi");
+
+        let mut stream = session.stream("And again").await.unwrap();
+        while let Some(item) = stream.next().await {
+            item.unwrap();
+        }
+        assert_eq!(session.context(), Some(vec![4, 5, 6]));
+
+        let received = mock.requests_to("/api/generate");
+        assert_eq!(received[0].body.as_ref().unwrap().get("context"), None);
+        assert_eq!(
+            received[1].body.as_ref().unwrap()["context"],
+            serde_json::json!([4, 5])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_non_stream_mock() {
+        let mock = MockOllama::start().await;
+        let request = CompletionRequestBuilder::default()
+            .model("llama3")
+            .prompt("good morning")
+            .stream(false)
+            .build()
+            .unwrap();
+
+        let response = mock.client().completion(request).await.unwrap();
+        let response = response.response().await.unwrap();
+        assert_eq!(response.response, "Hello from mock");
+        assert_eq!(response.context, Some(vec![1, 2, 3]));
+    }
+
     #[ignore]
     #[tokio::test]
     async fn test_generate_non_stream() {
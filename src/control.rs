@@ -0,0 +1,141 @@
+use std::{
+    future::{pending, Future},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use derive_builder::Builder;
+use tokio::time::{sleep_until, Instant};
+use tokio_stream::{Stream, StreamExt};
+
+use crate::errors::OllamaError;
+
+pub use tokio_util::sync::CancellationToken;
+
+/// Per-request cancellation and timeouts for chat and completion calls.
+/// When the token is cancelled or a timeout elapses, reading stops and the
+/// connection is closed so the server stops generating.
+#[derive(Debug, Clone, Default, Builder)]
+#[builder(default)]
+pub struct RequestControl {
+    /// Token used to abort the request at any time.
+    #[builder(setter(strip_option))]
+    pub cancellation: Option<CancellationToken>,
+
+    /// Maximum duration of the whole request, including reading the full response.
+    #[builder(setter(strip_option))]
+    pub timeout: Option<Duration>,
+
+    /// Maximum duration until the first chunk of the response is received.
+    #[builder(setter(strip_option))]
+    pub first_token_timeout: Option<Duration>,
+
+    /// Maximum duration between two chunks of the response.
+    #[builder(setter(strip_option))]
+    pub idle_timeout: Option<Duration>,
+}
+
+impl RequestControl {
+    pub(crate) fn start(&self) -> Watchdog {
+        Watchdog {
+            control: self.clone(),
+            started: Instant::now(),
+            last_chunk: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+/// Tracks the deadlines of one request started with a [`RequestControl`].
+#[derive(Debug, Clone)]
+pub(crate) struct Watchdog {
+    control: RequestControl,
+    started: Instant,
+    last_chunk: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Watchdog {
+    /// Record that a chunk of the response has been received.
+    pub(crate) fn touch(&self) {
+        *self.last_chunk.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Run `future` until it completes, the request is cancelled or a deadline elapses.
+    pub(crate) async fn run<F: Future>(&self, future: F) -> Result<F::Output, OllamaError> {
+        tokio::select! {
+            biased;
+            err = self.expired() => Err(err),
+            output = future => Ok(output),
+        }
+    }
+
+    /// Mark every chunk read from `input` as received.
+    pub(crate) fn track(
+        &self,
+        input: impl Stream<Item = Result<Bytes, reqwest::Error>> + Unpin + Send + Sync + 'static,
+    ) -> impl Stream<Item = Result<Bytes, reqwest::Error>> + Unpin + Send + Sync + 'static {
+        let watchdog = self.clone();
+        input.map(move |item| {
+            watchdog.touch();
+            item
+        })
+    }
+
+    async fn expired(&self) -> OllamaError {
+        let cancelled = async {
+            match &self.control.cancellation {
+                Some(token) => token.cancelled().await,
+                None => pending().await,
+            }
+        };
+
+        let timed_out = async {
+            loop {
+                let (deadline, reason) = match self.next_deadline() {
+                    Some(next) => next,
+                    None => pending().await,
+                };
+                if deadline <= Instant::now() {
+                    return reason;
+                }
+                sleep_until(deadline).await;
+            }
+        };
+
+        tokio::select! {
+            biased;
+            _ = cancelled => OllamaError::Cancelled,
+            reason = timed_out => OllamaError::Timeout(reason),
+        }
+    }
+
+    fn next_deadline(&self) -> Option<(Instant, String)> {
+        let last_chunk = *self.last_chunk.lock().unwrap();
+        let mut deadlines = vec![];
+
+        if let Some(timeout) = self.control.timeout {
+            deadlines.push((
+                self.started + timeout,
+                format!("request not completed within {timeout:?}"),
+            ));
+        }
+
+        match last_chunk {
+            None => {
+                if let Some(timeout) = self.control.first_token_timeout {
+                    deadlines.push((
+                        self.started + timeout,
+                        format!("no response received within {timeout:?}"),
+                    ));
+                }
+            }
+            Some(last) => {
+                if let Some(timeout) = self.control.idle_timeout {
+                    deadlines.push((last + timeout, format!("stream idle for {timeout:?}")));
+                }
+            }
+        }
+
+        deadlines.into_iter().min_by_key(|(deadline, _)| *deadline)
+    }
+}
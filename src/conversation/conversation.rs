@@ -0,0 +1,241 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chat_completion::{
+        message::{Message, Role},
+        request::ChatCompletionRequest,
+        response::ChatResponse,
+    },
+    completion::{request::CompletionRequest, response::CompletionResponse},
+    errors::OllamaError,
+    options::Options,
+};
+
+/// Version of the on-disk conversation format written by this crate.
+pub const CONVERSATION_FORMAT_VERSION: u32 = 1;
+
+/// A chat history that can be saved to disk and restored later, either as a
+/// single JSON document or as JSONL (one header line followed by one line per message).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Conversation {
+    /// Version of the on-disk format.
+    pub version: u32,
+
+    /// The model name.
+    pub model: String,
+
+    /// Additional model parameters used for every request of the conversation.
+    #[serde(default, skip_serializing_if = "crate::options::Options::is_default")]
+    pub options: Options,
+
+    /// System message used by `/api/generate` style sessions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+
+    /// The context returned by the last `/api/generate` response,
+    /// sent with the next prompt to keep a conversational memory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<Vec<usize>>,
+
+    /// The messages of the chat.
+    #[serde(default)]
+    pub messages: Vec<Message>,
+}
+
+/// The first line of a JSONL conversation file.
+#[derive(Serialize, Deserialize)]
+struct ConversationHeader {
+    version: u32,
+    model: String,
+    #[serde(default, skip_serializing_if = "crate::options::Options::is_default")]
+    options: Options,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    context: Option<Vec<usize>>,
+}
+
+impl Conversation {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            version: CONVERSATION_FORMAT_VERSION,
+            model: model.into(),
+            options: Options::default(),
+            system: None,
+            context: None,
+            messages: vec![],
+        }
+    }
+
+    pub fn with_options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    pub fn push(&mut self, message: Message) {
+        self.messages.push(message);
+    }
+
+    /// Append a user message with the given content.
+    pub fn push_user(&mut self, content: impl Into<String>) {
+        self.push(Message {
+            role: Role::User,
+            content: content.into(),
+            images: None,
+        });
+    }
+
+    /// Append the message of a (non-streamed or aggregated) chat response.
+    pub fn record_chat(&mut self, response: &ChatResponse) {
+        if let Some(message) = &response.message {
+            self.push(message.clone());
+        }
+    }
+
+    /// Record one `/api/generate` turn: the prompt, the generated text and
+    /// the returned context which will be sent with the next prompt.
+    pub fn record_completion(&mut self, prompt: impl Into<String>, response: &CompletionResponse) {
+        self.push_user(prompt);
+        self.push(Message {
+            role: Role::Assistant,
+            content: response.response.clone(),
+            images: None,
+        });
+        if response.context.is_some() {
+            self.context = response.context.clone();
+        }
+    }
+
+    /// Build a chat request continuing this conversation.
+    pub fn chat_request(&self) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: self.model.clone(),
+            messages: self.messages.clone(),
+            options: self.options.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Build a generate request for `prompt` chained to the stored context.
+    pub fn completion_request(&self, prompt: impl Into<String>) -> CompletionRequest {
+        CompletionRequest {
+            model: self.model.clone(),
+            prompt: prompt.into(),
+            options: self.options.clone(),
+            system: self.system.clone(),
+            context: self.context.clone(),
+            ..Default::default()
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, OllamaError> {
+        serde_json::to_string_pretty(self).map_err(|e| OllamaError::ParseError(e.to_string()))
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, OllamaError> {
+        let conversation: Self =
+            serde_json::from_str(content).map_err(|e| OllamaError::ParseError(e.to_string()))?;
+        check_version(conversation.version)?;
+        Ok(conversation)
+    }
+
+    /// Save the conversation as a single JSON document.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), OllamaError> {
+        fs::write(path, self.to_json()?).map_err(|e| OllamaError::IoError(e.to_string()))
+    }
+
+    /// Load a conversation saved with [`Conversation::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, OllamaError> {
+        let content = fs::read_to_string(path).map_err(|e| OllamaError::IoError(e.to_string()))?;
+        Self::from_json(&content)
+    }
+
+    /// Save the conversation as JSONL: a header line holding the model, options,
+    /// system message and context, followed by one line per message.
+    pub fn save_jsonl(&self, path: impl AsRef<Path>) -> Result<(), OllamaError> {
+        let file = fs::File::create(path).map_err(|e| OllamaError::IoError(e.to_string()))?;
+        let mut writer = BufWriter::new(file);
+
+        let header = ConversationHeader {
+            version: self.version,
+            model: self.model.clone(),
+            options: self.options.clone(),
+            system: self.system.clone(),
+            context: self.context.clone(),
+        };
+        write_json_line(&mut writer, &header)?;
+        for message in &self.messages {
+            write_json_line(&mut writer, message)?;
+        }
+
+        writer
+            .flush()
+            .map_err(|e| OllamaError::IoError(e.to_string()))
+    }
+
+    /// Load a conversation saved with [`Conversation::save_jsonl`].
+    pub fn load_jsonl(path: impl AsRef<Path>) -> Result<Self, OllamaError> {
+        let file = fs::File::open(path).map_err(|e| OllamaError::IoError(e.to_string()))?;
+        let mut lines = BufReader::new(file).lines();
+
+        let header = match lines.next() {
+            Some(line) => line.map_err(|e| OllamaError::IoError(e.to_string()))?,
+            None => {
+                return Err(OllamaError::ParseError(String::from(
+                    "empty conversation file",
+                )))
+            }
+        };
+        let header: ConversationHeader =
+            serde_json::from_str(&header).map_err(|e| OllamaError::ParseError(e.to_string()))?;
+        check_version(header.version)?;
+
+        let mut messages = vec![];
+        for line in lines {
+            let line = line.map_err(|e| OllamaError::IoError(e.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let message =
+                serde_json::from_str(&line).map_err(|e| OllamaError::ParseError(e.to_string()))?;
+            messages.push(message);
+        }
+
+        Ok(Self {
+            version: header.version,
+            model: header.model,
+            options: header.options,
+            system: header.system,
+            context: header.context,
+            messages,
+        })
+    }
+}
+
+fn check_version(version: u32) -> Result<(), OllamaError> {
+    if version == 0 || version > CONVERSATION_FORMAT_VERSION {
+        return Err(OllamaError::ParseError(format!(
+            "unsupported conversation format version: {version}"
+        )));
+    }
+    Ok(())
+}
+
+fn write_json_line<T: Serialize>(writer: &mut impl Write, value: &T) -> Result<(), OllamaError> {
+    serde_json::to_writer(&mut *writer, value)
+        .map_err(|e| OllamaError::ParseError(e.to_string()))?;
+    writer
+        .write_all(b"\n")
+        .map_err(|e| OllamaError::IoError(e.to_string()))
+}
@@ -0,0 +1,6 @@
+pub mod conversation;
+
+// test module
+mod test_conversation;
+
+pub use conversation::{Conversation, CONVERSATION_FORMAT_VERSION};
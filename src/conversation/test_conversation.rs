@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        chat_completion::message::{MessageBuilder, Role},
+        completion::response::CompletionResponse,
+        conversation::Conversation,
+        errors::OllamaError,
+        options::OptionsBuilder,
+    };
+
+    fn sample() -> Conversation {
+        let options = OptionsBuilder::default().seed(42).build().unwrap();
+        let mut conversation = Conversation::new("llama3:8b").with_options(options);
+        conversation.push(
+            MessageBuilder::default()
+                .role(Role::System)
+                .content("You are a helpful assistant.")
+                .build()
+                .unwrap(),
+        );
+        conversation.record_completion(
+            "Hello",
+            &CompletionResponse {
+                response: String::from("Hi there"),
+                done: true,
+                context: Some(vec![1, 2, 3]),
+                ..Default::default()
+            },
+        );
+        conversation
+    }
+
+    #[test]
+    fn test_record_completion() {
+        let conversation = sample();
+        assert_eq!(conversation.messages.len(), 3);
+        assert_eq!(conversation.messages[2].role, Role::Assistant);
+        assert_eq!(conversation.context, Some(vec![1, 2, 3]));
+
+        let request = conversation.completion_request("And then?");
+        assert_eq!(request.context, Some(vec![1, 2, 3]));
+        assert_eq!(request.options.seed, Some(42));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let conversation = sample();
+        let path = std::env::temp_dir().join("pure_ollama_test_conversation.json");
+        conversation.save(&path).unwrap();
+        let loaded = Conversation::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, conversation);
+    }
+
+    #[test]
+    fn test_jsonl_round_trip() {
+        let conversation = sample().with_system("Be brief.");
+        let path = std::env::temp_dir().join("pure_ollama_test_conversation.jsonl");
+        conversation.save_jsonl(&path).unwrap();
+        let loaded = Conversation::load_jsonl(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, conversation);
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let content = "{\"version\":99,\"model\":\"llama3\",\"messages\":[]}";
+        let err = Conversation::from_json(content).unwrap_err();
+        assert_eq!(
+            err,
+            OllamaError::ParseError(String::from("unsupported conversation format version: 99"))
+        );
+    }
+}
@@ -14,9 +14,46 @@ pub enum OllamaError {
     #[error("Ollama Error: {0}")]
     OllamaError(String),
 
+    #[error("Server Error: {0}")]
+    ServerError(String),
+
     #[error("Stream Error: {0}")]
     StreamError(String),
 
     #[error("Invalid Parameter: {0}")]
     InvalidParameter(String),
+
+    #[error("IO Error: {0}")]
+    IoError(String),
+
+    #[error("Request Cancelled")]
+    Cancelled,
+
+    #[error("Timeout: {0}")]
+    Timeout(String),
+
+    #[error("Circuit Open: {0}")]
+    CircuitOpen(String),
+
+    #[error("Queue Full: {0}")]
+    QueueFull(String),
+}
+
+impl OllamaError {
+    /// The error for a response with the unsuccessful `status` and `body`:
+    /// [`OllamaError::ServerError`] for a server error (5xx), else [`OllamaError::OllamaError`].
+    pub(crate) fn from_status(status: reqwest::StatusCode, body: String) -> Self {
+        match status.is_server_error() {
+            true => Self::ServerError(body),
+            false => Self::OllamaError(body),
+        }
+    }
+
+    /// True if the server reported that the requested model is not available locally.
+    pub fn is_model_not_found(&self) -> bool {
+        match self {
+            Self::OllamaError(err_msg) => err_msg.contains("try pulling it first"),
+            _ => false,
+        }
+    }
 }
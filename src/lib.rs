@@ -1,7 +1,30 @@
+pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
+pub mod cassette;
 pub mod chat_completion;
+pub mod client;
 pub mod completion;
+pub mod control;
+pub mod conversation;
 pub mod errors;
 pub mod format;
 pub mod model;
+#[cfg(feature = "openai")]
+pub mod openai;
 pub mod options;
+pub mod pool;
+#[cfg(feature = "proxy")]
+pub mod proxy;
 pub mod response;
+pub mod scheduler;
+pub mod single_flight;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+// test module
+mod test_api;
+mod test_control;
+mod test_options;
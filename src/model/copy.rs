@@ -0,0 +1,20 @@
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+use crate::{client::OllamaClient, errors::OllamaError};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+pub struct CopyModelRequest {
+    /// Name of the existing model.
+    #[builder(setter(into))]
+    pub source: String,
+
+    /// Name of the new model.
+    #[builder(setter(into))]
+    pub destination: String,
+}
+
+/// Copy a model. Creates a model with another name from an existing model.
+pub async fn copy(request: CopyModelRequest) -> Result<(), OllamaError> {
+    OllamaClient::default().copy(request).await
+}
@@ -6,14 +6,16 @@ use serde::{Deserialize, Serialize};
 use tokio_stream::{Stream, StreamExt};
 
 use crate::{
+    client::OllamaClient,
     errors::OllamaError,
-    response::{OllamaStream, StreamHandler},
+    response::{split_lines, OllamaResponse, OllamaStream, StreamHandler},
 };
 
-#[derive(Debug, Clone, Serialize, Builder)]
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
 pub struct CreateModelRequest {
     /// Name of the model to create.
     #[builder(setter(into))]
+    #[serde(alias = "model")]
     pub name: String,
 
     /// Contents of the Modelfile.
@@ -35,7 +37,7 @@ pub struct CreateModelRequest {
 
 /// A stream of JSON objects. Notice that the final JSON
 /// object shows "status": "success" if the response is a stream.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateModelResponse {
     pub status: String,
 }
@@ -43,8 +45,10 @@ pub struct CreateModelResponse {
 #[async_trait]
 impl StreamHandler for CreateModelResponse {
     async fn adapt_stream(
-        mut input: impl Stream<Item = Result<Bytes, reqwest::Error>> + Unpin + Send + Sync + 'static,
+        input: impl Stream<Item = Result<Bytes, reqwest::Error>> + Unpin + Send + Sync + 'static,
     ) -> OllamaStream<Self> {
+        // Progress lines may be merged or split between chunks.
+        let mut input = Box::pin(split_lines(input));
         let adapted = stream! {
             while let Some(item) = input.next().await {
                 match item {
@@ -76,3 +80,10 @@ impl StreamHandler for CreateModelResponse {
         Ok(Self { status })
     }
 }
+
+/// Create a model from a Modelfile.
+pub async fn create(
+    request: CreateModelRequest,
+) -> Result<OllamaResponse<CreateModelResponse>, OllamaError> {
+    OllamaClient::default().create(request).await
+}
@@ -0,0 +1,17 @@
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+use crate::{client::OllamaClient, errors::OllamaError};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+pub struct DeleteModelRequest {
+    /// Name of the model to delete.
+    #[builder(setter(into))]
+    #[serde(alias = "model")]
+    pub name: String,
+}
+
+/// Delete a model and its data.
+pub async fn delete(request: DeleteModelRequest) -> Result<(), OllamaError> {
+    OllamaClient::default().delete(request).await
+}
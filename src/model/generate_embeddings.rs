@@ -0,0 +1,55 @@
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    client::OllamaClient,
+    errors::OllamaError,
+    options::{GetOptionsBuilder, Options, OptionsBuilder, OptionsConstructor},
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Builder)]
+pub struct EmbeddingsRequest {
+    /// Name of model to generate embeddings from.
+    #[builder(setter(into))]
+    pub model: String,
+
+    /// Text to generate embeddings for.
+    #[builder(setter(into))]
+    pub prompt: String,
+
+    /// Additional model parameters listed in the documentation for the Modelfile
+    /// such as temperature.
+    #[builder(setter(strip_option))]
+    #[builder(field(
+        ty = "crate::options::OptionsBuilder",
+        build = r#"self.options.build().unwrap()"#
+    ))]
+    #[serde(default, skip_serializing_if = "crate::options::Options::is_default")]
+    pub options: Options,
+
+    /// Kontrols how long the model will stay loaded into
+    /// memory following the request (default: 5m).
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingsResponse {
+    pub embedding: Vec<f64>,
+}
+
+impl GetOptionsBuilder for EmbeddingsRequestBuilder {
+    fn get_options_builder(&mut self) -> &mut OptionsBuilder {
+        &mut self.options
+    }
+}
+
+impl OptionsConstructor for EmbeddingsRequestBuilder {}
+
+/// Generate embeddings from a model.
+pub async fn generate_embeddings(
+    request: EmbeddingsRequest,
+) -> Result<EmbeddingsResponse, OllamaError> {
+    OllamaClient::default().generate_embeddings(request).await
+}
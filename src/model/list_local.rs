@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{client::OllamaClient, errors::OllamaError};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_model: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub families: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameter_size: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantization_level: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalModel {
+    /// The model name.
+    pub name: String,
+
+    pub modified_at: String,
+
+    /// Size of the model in bytes.
+    pub size: u64,
+
+    pub digest: String,
+
+    #[serde(default)]
+    pub details: ModelDetails,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListLocalModelsResponse {
+    pub models: Vec<LocalModel>,
+}
+
+/// List models that are available locally.
+pub async fn list_local() -> Result<ListLocalModelsResponse, OllamaError> {
+    OllamaClient::default().list_local().await
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+use super::list_local::ModelDetails;
+use crate::{client::OllamaClient, errors::OllamaError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunningModel {
+    /// The model name.
+    pub name: String,
+
+    pub model: String,
+
+    /// Size of the model in bytes.
+    pub size: u64,
+
+    pub digest: String,
+
+    #[serde(default)]
+    pub details: ModelDetails,
+
+    /// When the model will be unloaded from memory.
+    pub expires_at: String,
+
+    /// Size of the model loaded into the memory of the GPU, in bytes.
+    #[serde(default)]
+    pub size_vram: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListRunningModelsResponse {
+    pub models: Vec<RunningModel>,
+}
+
+/// List models that are currently loaded into memory.
+pub async fn list_running() -> Result<ListRunningModelsResponse, OllamaError> {
+    OllamaClient::default().list_running().await
+}
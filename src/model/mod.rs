@@ -1,3 +1,22 @@
+pub mod copy;
 pub mod create;
+pub mod delete;
+pub mod generate_embeddings;
+pub mod list_local;
+pub mod list_running;
+pub mod pull;
+pub mod push;
+pub mod show_info;
+
+/// The model name with its tag, `llama3` being the same model as `llama3:latest`.
+pub(crate) fn canonical_name(model: &str) -> String {
+    let name = model.rsplit('/').next().unwrap_or(model);
+    match name.contains(':') {
+        true => model.to_string(),
+        false => format!("{model}:latest"),
+    }
+}
 
 mod test_create;
+mod test_model;
+mod test_pull;
@@ -0,0 +1,98 @@
+use async_stream::stream;
+use async_trait::async_trait;
+use bytes::Bytes;
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+use tokio_stream::{Stream, StreamExt};
+
+use crate::{
+    client::OllamaClient,
+    errors::OllamaError,
+    response::{split_lines, OllamaResponse, OllamaStream, StreamHandler},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+pub struct PushModelRequest {
+    /// Name of the model to push in the form of <namespace>/<model>:<tag>.
+    #[builder(setter(into))]
+    #[serde(alias = "model")]
+    pub name: String,
+
+    /// Allow insecure connections to the library. Only use this if you
+    /// are pushing to your library during development.
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insecure: Option<bool>,
+
+    /// If false the response will be returned as a
+    /// single response object, rather than a stream of objects.
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+}
+
+/// A stream of JSON objects describing the progress of the upload.
+/// The final JSON object shows "status": "success".
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PushModelResponse {
+    #[serde(default)]
+    pub status: String,
+
+    /// Digest of the layer being uploaded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+
+    /// Size in bytes of the layer being uploaded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+
+    /// Set when the server failed to push the model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[async_trait]
+impl StreamHandler for PushModelResponse {
+    async fn adapt_stream(
+        input: impl Stream<Item = Result<Bytes, reqwest::Error>> + Unpin + Send + Sync + 'static,
+    ) -> OllamaStream<Self> {
+        // Progress lines may be merged or split between chunks.
+        let mut input = Box::pin(split_lines(input));
+        let adapted = stream! {
+            while let Some(item) = input.next().await {
+                match item {
+                    Ok(inner) => {
+                        match serde_json::from_slice::<PushModelResponse>(&inner) {
+                            Ok(PushModelResponse { error: Some(e), .. }) => yield Err(OllamaError::OllamaError(e)),
+                            Ok(content) => yield Ok(content),
+                            Err(e) => yield Err(OllamaError::InvalidResponse(e.to_string()))
+                        }
+                    },
+                    Err(e) => yield Err(OllamaError::StreamError(e.to_string()))
+                }
+            }
+        };
+
+        Box::pin(adapted)
+    }
+
+    async fn stream_to_response(
+        input: impl Stream<Item = Result<Bytes, reqwest::Error>> + Unpin + Send + Sync + 'static,
+    ) -> Result<Self, OllamaError> {
+        let mut adapted_stream = Self::adapt_stream(input).await;
+
+        let mut last = Self::default();
+        while let Some(item) = adapted_stream.next().await {
+            last = item?;
+        }
+
+        Ok(last)
+    }
+}
+
+/// Upload a model to a model library. Requires registering for ollama.ai and adding a public key first.
+pub async fn push(
+    request: PushModelRequest,
+) -> Result<OllamaResponse<PushModelResponse>, OllamaError> {
+    OllamaClient::default().push(request).await
+}
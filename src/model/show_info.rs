@@ -0,0 +1,44 @@
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+use super::list_local::ModelDetails;
+use crate::{client::OllamaClient, errors::OllamaError};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+pub struct ShowModelRequest {
+    /// Name of the model to show.
+    #[builder(setter(into))]
+    #[serde(alias = "model")]
+    pub name: String,
+
+    /// If set to true, returns full data for verbose response fields.
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verbose: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShowModelResponse {
+    #[serde(default)]
+    pub modelfile: String,
+
+    #[serde(default)]
+    pub parameters: String,
+
+    #[serde(default)]
+    pub template: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+
+    #[serde(default)]
+    pub details: ModelDetails,
+}
+
+/// Show information about a model including details, modelfile, template, parameters, license and system prompt.
+pub async fn show_info(request: ShowModelRequest) -> Result<ShowModelResponse, OllamaError> {
+    OllamaClient::default().show_info(request).await
+}
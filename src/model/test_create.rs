@@ -2,4 +2,4 @@
 mod tests {
     #[tokio::test]
     async fn test_create_model() {}
-}
\ No newline at end of file
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use tokio_stream::StreamExt;
+
+    use crate::{
+        model::{
+            copy::CopyModelRequestBuilder, delete::DeleteModelRequestBuilder,
+            generate_embeddings::EmbeddingsRequestBuilder, push::PushModelRequestBuilder,
+            show_info::ShowModelRequestBuilder,
+        },
+        options::OptionsConstructor,
+        testing::{MockOllama, MockResponse},
+    };
+
+    #[test]
+    fn test_embeddings_request() {
+        let request = EmbeddingsRequestBuilder::default()
+            .model("all-minilm")
+            .prompt("Here is an article about llamas...")
+            .seed(1)
+            .build()
+            .unwrap();
+
+        let serialized = serde_json::to_string(&request).unwrap();
+        let expect = "{\"model\":\"all-minilm\",\"prompt\":\"Here is an article about llamas...\",\"options\":{\"seed\":1}}";
+        assert_eq!(serialized, expect);
+    }
+
+    #[tokio::test]
+    async fn test_model_management_mock() {
+        let mock = MockOllama::start().await;
+        let client = mock.client();
+
+        mock.respond(
+            "/api/tags",
+            MockResponse::json(serde_json::json!({
+                "models": [{
+                    "name": "llama3:latest",
+                    "modified_at": "2024-06-01T00:00:00Z",
+                    "size": 4661224676u64,
+                    "digest": "365c0bd3c000",
+                    "details": { "family": "llama", "parameter_size": "8.0B" },
+                }]
+            })),
+        );
+        let local = client.list_local().await.unwrap();
+        assert_eq!(local.models[0].name, "llama3:latest");
+        assert_eq!(
+            local.models[0].details.parameter_size.as_deref(),
+            Some("8.0B")
+        );
+
+        assert!(client.list_running().await.unwrap().models.is_empty());
+
+        let request = ShowModelRequestBuilder::default()
+            .name("llama3")
+            .build()
+            .unwrap();
+        let info = client.show_info(request).await.unwrap();
+        assert_eq!(info.modelfile, "FROM llama3");
+
+        let request = CopyModelRequestBuilder::default()
+            .source("llama3")
+            .destination("llama3-backup")
+            .build()
+            .unwrap();
+        client.copy(request).await.unwrap();
+
+        let request = DeleteModelRequestBuilder::default()
+            .name("llama3-backup")
+            .build()
+            .unwrap();
+        client.delete(request).await.unwrap();
+        assert_eq!(mock.requests_to("/api/delete")[0].method, "DELETE");
+
+        let request = EmbeddingsRequestBuilder::default()
+            .model("all-minilm")
+            .prompt("llamas")
+            .build()
+            .unwrap();
+        let embeddings = client.generate_embeddings(request).await.unwrap();
+        assert_eq!(embeddings.embedding.len(), 3);
+
+        let request = PushModelRequestBuilder::default()
+            .name("me/llama3")
+            .build()
+            .unwrap();
+        let mut stream = client
+            .push(request)
+            .await
+            .unwrap()
+            .as_stream()
+            .await
+            .unwrap();
+        let mut status = String::default();
+        while let Some(item) = stream.next().await {
+            status = item.unwrap().status;
+        }
+        assert_eq!(status, "success");
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use tokio_stream::StreamExt;
+
+    use crate::{
+        model::pull::{pull, PullModelRequestBuilder, PullModelResponse},
+        testing::{MockOllama, MockResponse},
+    };
+
+    #[test]
+    fn test_pull_request() {
+        let request = PullModelRequestBuilder::default()
+            .name("llama3")
+            .stream(false)
+            .build()
+            .unwrap();
+
+        let serialized = serde_json::to_string(&request).unwrap();
+        assert_eq!(serialized, "{\"name\":\"llama3\",\"stream\":false}");
+    }
+
+    #[test]
+    fn test_pull_response() {
+        let content = "{\"status\":\"downloading digestname\",\"digest\":\"digestname\",\"total\":2142590208,\"completed\":241970}";
+        let response: PullModelResponse = serde_json::from_str(content).unwrap();
+        assert_eq!(response.total, Some(2142590208));
+        assert_eq!(response.completed, Some(241970));
+        assert_eq!(response.error, None);
+    }
+
+    #[tokio::test]
+    async fn test_pull_progress_across_chunks() {
+        let mock = MockOllama::start().await;
+        let body = concat!(
+            "{\"status\":\"pulling manifest\"}\n",
+            "{\"status\":\"downloading\",\"total\":10,\"completed\":5}\n",
+            "{\"status\":\"success\"}\n",
+        );
+        // The first two lines in one chunk, the last one split in two.
+        let split = body.rfind("success").unwrap();
+        mock.respond(
+            "/api/pull",
+            MockResponse::chunks(vec![
+                body.as_bytes()[..split].to_vec(),
+                body.as_bytes()[split..].to_vec(),
+            ]),
+        );
+        let request = PullModelRequestBuilder::default()
+            .name("llama3")
+            .build()
+            .unwrap();
+
+        let mut stream = mock
+            .client()
+            .pull(request)
+            .await
+            .unwrap()
+            .as_stream()
+            .await
+            .unwrap();
+        let mut statuses = vec![];
+        while let Some(item) = stream.next().await {
+            statuses.push(item.unwrap().status);
+        }
+        assert_eq!(statuses, ["pulling manifest", "downloading", "success"]);
+    }
+
+    #[ignore]
+    #[tokio::test]
+    async fn test_pull_stream() {
+        let request = PullModelRequestBuilder::default()
+            .name("llama3:8b")
+            .build()
+            .unwrap();
+
+        let mut stream = pull(request).await.unwrap().as_stream().await.unwrap();
+        let mut statuses = vec![];
+        while let Some(item) = stream.next().await {
+            statuses.push(item.unwrap().status);
+        }
+        assert_eq!(
+            statuses.first().map(String::as_str),
+            Some("pulling manifest")
+        );
+        assert_eq!(statuses.last().map(String::as_str), Some("success"));
+    }
+}
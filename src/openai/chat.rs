@@ -0,0 +1,373 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+use tokio_stream::{Stream, StreamExt};
+
+use super::{
+    common::{null_as_default, ResponseFormat, SamplingParams, Usage},
+    sse::sse_stream,
+};
+use crate::{
+    chat_completion::{
+        message::{Message, Role},
+        request::ChatCompletionRequest as OllamaChatRequest,
+        response::ChatResponse,
+    },
+    errors::OllamaError,
+    response::{OllamaStream, StreamHandler},
+};
+
+/// Prefix of the data URLs carrying the base64-encoded images of a message.
+const IMAGE_URL_PREFIX: &str = "data:image/png;base64,";
+
+/// A chat message in the OpenAI format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: Role,
+
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub content: MessageContent,
+}
+
+/// The content of a message, plain text or a list of text and image parts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+impl Default for MessageContent {
+    fn default() -> Self {
+        Self::Text(String::default())
+    }
+}
+
+impl MessageContent {
+    /// The text of the content, text parts being concatenated.
+    pub fn text(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Parts(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    ContentPart::Text { text } => Some(text.as_str()),
+                    ContentPart::ImageUrl { .. } => None,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrl },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageUrl {
+    pub url: String,
+}
+
+/// Images are sent as base64 data URLs.
+impl From<Message> for ChatMessage {
+    fn from(value: Message) -> Self {
+        let content = match value.images {
+            Some(images) if !images.is_empty() => {
+                let mut parts = vec![ContentPart::Text {
+                    text: value.content,
+                }];
+                parts.extend(images.into_iter().map(|image| ContentPart::ImageUrl {
+                    image_url: ImageUrl {
+                        url: format!("{IMAGE_URL_PREFIX}{image}"),
+                    },
+                }));
+                MessageContent::Parts(parts)
+            }
+            _ => MessageContent::Text(value.content),
+        };
+
+        Self {
+            role: value.role,
+            content,
+        }
+    }
+}
+
+/// Only images given as base64 data URLs are kept, remote URLs are dropped.
+impl From<ChatMessage> for Message {
+    fn from(value: ChatMessage) -> Self {
+        let images: Vec<String> = match &value.content {
+            MessageContent::Text(_) => vec![],
+            MessageContent::Parts(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    ContentPart::ImageUrl { image_url } => image_url
+                        .url
+                        .split_once(";base64,")
+                        .filter(|(scheme, _)| scheme.starts_with("data:"))
+                        .map(|(_, image)| image.to_string()),
+                    ContentPart::Text { .. } => None,
+                })
+                .collect(),
+        };
+
+        Self {
+            role: value.role,
+            content: value.content.text(),
+            images: (!images.is_empty()).then_some(images),
+        }
+    }
+}
+
+/// Request of `/v1/chat/completions`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Builder)]
+pub struct ChatCompletionRequest {
+    #[builder(setter(into))]
+    pub model: String,
+
+    pub messages: Vec<ChatMessage>,
+
+    #[builder(default)]
+    #[serde(flatten)]
+    pub sampling: SamplingParams,
+
+    #[builder(setter(strip_option), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+
+    /// Set by [`crate::openai::OpenAiClient`] depending on the method called.
+    #[builder(setter(strip_option), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+}
+
+impl From<OllamaChatRequest> for ChatCompletionRequest {
+    fn from(value: OllamaChatRequest) -> Self {
+        Self {
+            sampling: SamplingParams::from(&value.options),
+            response_format: ResponseFormat::from_format(value.format.as_ref()),
+            model: value.model,
+            messages: value.messages.into_iter().map(ChatMessage::from).collect(),
+            stream: value.stream,
+        }
+    }
+}
+
+/// Fails if the sampling parameters have no Ollama equivalent.
+impl TryFrom<ChatCompletionRequest> for OllamaChatRequest {
+    type Error = OllamaError;
+
+    fn try_from(value: ChatCompletionRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            options: (&value.sampling).try_into()?,
+            format: value.response_format.and_then(|f| f.to_format()),
+            model: value.model,
+            messages: value.messages.into_iter().map(Message::from).collect(),
+            stream: value.stream,
+            keep_alive: None,
+        })
+    }
+}
+
+/// Response of `/v1/chat/completions`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChatCompletion {
+    pub id: String,
+
+    pub object: String,
+
+    /// Unix timestamp in seconds.
+    pub created: u64,
+
+    pub model: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
+
+    pub choices: Vec<ChatChoice>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+impl ChatCompletion {
+    /// Translate a complete Ollama chat response, the usage being taken
+    /// from `prompt_eval_count` and `eval_count`.
+    pub fn from_ollama(id: impl Into<String>, created: u64, response: ChatResponse) -> Self {
+        let message = response.message.clone().unwrap_or(Message {
+            role: Role::Assistant,
+            content: String::default(),
+            images: None,
+        });
+
+        Self {
+            id: id.into(),
+            object: String::from("chat.completion"),
+            created,
+            system_fingerprint: None,
+            choices: vec![ChatChoice {
+                index: 0,
+                message: message.into(),
+                finish_reason: Some(finish_reason(&response)),
+            }],
+            usage: Some(usage(&response)),
+            model: response.model,
+        }
+    }
+
+    /// The message of the first choice.
+    pub fn message(&self) -> Option<Message> {
+        self.choices
+            .first()
+            .map(|choice| choice.message.clone().into())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatChoice {
+    pub index: usize,
+
+    pub message: ChatMessage,
+
+    /// Why generation stopped, `stop` or `length`.
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// A chunk streamed by `/v1/chat/completions`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChatCompletionChunk {
+    pub id: String,
+
+    pub object: String,
+
+    /// Unix timestamp in seconds.
+    pub created: u64,
+
+    pub model: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
+
+    pub choices: Vec<ChunkChoice>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+impl ChatCompletionChunk {
+    /// Translate one chunk of an Ollama chat stream. The final chunk carries
+    /// the finish reason and the usage.
+    pub fn from_ollama(id: impl Into<String>, created: u64, response: ChatResponse) -> Self {
+        let usage = response.done.then(|| usage(&response));
+        let finish_reason = response.done.then(|| finish_reason(&response));
+        let delta = match response.message {
+            Some(message) => Delta {
+                role: Some(message.role),
+                content: Some(message.content),
+            },
+            None => Delta::default(),
+        };
+
+        Self {
+            id: id.into(),
+            object: String::from("chat.completion.chunk"),
+            created,
+            model: response.model,
+            system_fingerprint: None,
+            choices: vec![ChunkChoice {
+                index: 0,
+                delta,
+                finish_reason,
+            }],
+            usage,
+        }
+    }
+
+    /// The content added by the first choice.
+    pub fn content(&self) -> &str {
+        self.choices
+            .first()
+            .and_then(|choice| choice.delta.content.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChunkChoice {
+    pub index: usize,
+
+    pub delta: Delta,
+
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// The part of the message added by a chunk.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Delta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<Role>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+fn usage(response: &ChatResponse) -> Usage {
+    Usage::new(
+        response.prompt_eval_count.unwrap_or_default(),
+        response.eval_count.unwrap_or_default(),
+    )
+}
+
+/// `length` if generation hit `num_predict`, `stop` otherwise.
+fn finish_reason(response: &ChatResponse) -> String {
+    match response.done_reason.as_deref() {
+        Some("length") => String::from("length"),
+        _ => String::from("stop"),
+    }
+}
+
+#[async_trait]
+impl StreamHandler for ChatCompletionChunk {
+    async fn adapt_stream(
+        input: impl Stream<Item = Result<Bytes, reqwest::Error>> + Unpin + Send + Sync + 'static,
+    ) -> OllamaStream<Self> {
+        sse_stream(input)
+    }
+
+    /// Merge the chunks into one whose first choice holds the whole content.
+    async fn stream_to_response(
+        input: impl Stream<Item = Result<Bytes, reqwest::Error>> + Unpin + Send + Sync + 'static,
+    ) -> Result<Self, OllamaError> {
+        let mut adapted_stream = Self::adapt_stream(input).await;
+        let mut merged: Option<Self> = None;
+        let mut content = String::default();
+        while let Some(item) = adapted_stream.next().await {
+            let item = item?;
+            content += item.content();
+            merged = match merged {
+                None => Some(item),
+                Some(mut merged) => {
+                    let finish_reason = item.choices.first().and_then(|c| c.finish_reason.clone());
+                    if let Some(choice) = merged.choices.first_mut() {
+                        choice.finish_reason = finish_reason.or(choice.finish_reason.take());
+                    }
+                    merged.usage = item.usage.or(merged.usage);
+                    Some(merged)
+                }
+            };
+        }
+
+        let mut merged =
+            merged.ok_or_else(|| OllamaError::InvalidResponse(String::from("empty stream")))?;
+        if let Some(choice) = merged.choices.first_mut() {
+            choice.delta.content = Some(content);
+        }
+        Ok(merged)
+    }
+}
@@ -0,0 +1,148 @@
+use super::{
+    chat::{ChatCompletion, ChatCompletionChunk, ChatCompletionRequest},
+    completions::{Completion, CompletionRequest},
+    embeddings::{EmbeddingList, EmbeddingsRequest},
+    models::{Model, ModelList},
+};
+use crate::{
+    client::OllamaClient, control::RequestControl, errors::OllamaError, response::OllamaStream,
+};
+
+/// A client for the OpenAI-compatible `/v1` endpoints of Ollama, or of any other
+/// OpenAI-compatible server. Requests go through an [`OllamaClient`], so its retry
+/// policy and cassette apply to them as well.
+#[derive(Debug, Clone, Default)]
+pub struct OpenAiClient {
+    client: OllamaClient,
+}
+
+impl From<OllamaClient> for OpenAiClient {
+    fn from(value: OllamaClient) -> Self {
+        Self { client: value }
+    }
+}
+
+impl OpenAiClient {
+    /// Create a client for the server at `host`, without the `/v1` suffix.
+    pub fn new(host: impl Into<String>) -> Self {
+        OllamaClient::new(host).into()
+    }
+
+    /// Send `api_key` as a bearer token with every request.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.client.api_key = Some(api_key.into());
+        self
+    }
+
+    #[inline]
+    pub fn inner(&self) -> &OllamaClient {
+        &self.client
+    }
+
+    /// Generate the next message of a chat.
+    pub async fn chat(
+        &self,
+        mut request: ChatCompletionRequest,
+    ) -> Result<ChatCompletion, OllamaError> {
+        request.stream = Some(false);
+        self.client
+            .post(
+                "/v1/chat/completions",
+                &request,
+                &RequestControl::default(),
+                false,
+            )
+            .await?
+            .response()
+            .await
+    }
+
+    /// Stream the next message of a chat.
+    pub async fn chat_stream(
+        &self,
+        mut request: ChatCompletionRequest,
+    ) -> Result<OllamaStream<ChatCompletionChunk>, OllamaError> {
+        request.stream = Some(true);
+        self.client
+            .post(
+                "/v1/chat/completions",
+                &request,
+                &RequestControl::default(),
+                true,
+            )
+            .await?
+            .as_stream()
+            .await
+    }
+
+    /// Generate a completion for a prompt.
+    pub async fn completion(
+        &self,
+        mut request: CompletionRequest,
+    ) -> Result<Completion, OllamaError> {
+        request.stream = Some(false);
+        self.client
+            .post(
+                "/v1/completions",
+                &request,
+                &RequestControl::default(),
+                false,
+            )
+            .await?
+            .response()
+            .await
+    }
+
+    /// Stream a completion for a prompt.
+    pub async fn completion_stream(
+        &self,
+        mut request: CompletionRequest,
+    ) -> Result<OllamaStream<Completion>, OllamaError> {
+        request.stream = Some(true);
+        self.client
+            .post(
+                "/v1/completions",
+                &request,
+                &RequestControl::default(),
+                true,
+            )
+            .await?
+            .as_stream()
+            .await
+    }
+
+    /// Generate one embedding per input.
+    pub async fn embeddings(
+        &self,
+        request: EmbeddingsRequest,
+    ) -> Result<EmbeddingList, OllamaError> {
+        self.client
+            .post(
+                "/v1/embeddings",
+                &request,
+                &RequestControl::default(),
+                false,
+            )
+            .await?
+            .response()
+            .await
+    }
+
+    /// List the available models.
+    pub async fn models(&self) -> Result<ModelList, OllamaError> {
+        self.client
+            .get("/v1/models", &RequestControl::default())
+            .await?
+            .response()
+            .await
+    }
+
+    /// Get a model by name.
+    pub async fn model(&self, model: &str) -> Result<Model, OllamaError> {
+        self.client
+            .get(&format!("/v1/models/{model}"), &RequestControl::default())
+            .await?
+            .response()
+            .await
+    }
+}
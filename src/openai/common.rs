@@ -0,0 +1,186 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{errors::OllamaError, format::Format, options::Options};
+
+/// Sampling parameters shared by chat and completion requests.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SamplingParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+
+    /// Maximum number of tokens to generate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<i32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i32>,
+
+    /// Stop sequences, sent as a list but also accepted as a single string.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "string_or_list"
+    )]
+    pub stop: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+}
+
+/// Only the parameters with an OpenAI equivalent are kept.
+impl From<&Options> for SamplingParams {
+    fn from(value: &Options) -> Self {
+        Self {
+            temperature: value.temperature,
+            top_p: value.top_p,
+            max_tokens: value.num_predict,
+            seed: value.seed,
+            stop: value.stop.clone().map(|stop| vec![stop]),
+            frequency_penalty: value.frequency_penalty,
+            presence_penalty: value.presence_penalty,
+        }
+    }
+}
+
+/// Fails if several stop sequences are set, as [`Options::stop`] holds one.
+impl TryFrom<&SamplingParams> for Options {
+    type Error = OllamaError;
+
+    fn try_from(value: &SamplingParams) -> Result<Self, Self::Error> {
+        let stop = match value.stop.as_deref() {
+            None | Some([]) => None,
+            Some([stop]) => Some(stop.clone()),
+            Some(_) => {
+                return Err(OllamaError::InvalidParameter(String::from(
+                    "stop: only one stop sequence is supported",
+                )))
+            }
+        };
+        Ok(Self {
+            temperature: value.temperature,
+            top_p: value.top_p,
+            num_predict: value.max_tokens,
+            seed: value.seed,
+            stop,
+            frequency_penalty: value.frequency_penalty,
+            presence_penalty: value.presence_penalty,
+            ..Default::default()
+        })
+    }
+}
+
+/// Format of the generated content.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    Text,
+    JsonObject,
+}
+
+impl ResponseFormat {
+    pub(crate) fn from_format(format: Option<&Format>) -> Option<Self> {
+        format.map(|_| Self::JsonObject)
+    }
+
+    pub(crate) fn to_format(&self) -> Option<Format> {
+        match self {
+            Self::Text => None,
+            Self::JsonObject => Some(Format::JSON),
+        }
+    }
+}
+
+/// Token counts of a request.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    #[serde(default)]
+    pub prompt_tokens: usize,
+
+    #[serde(default)]
+    pub completion_tokens: usize,
+
+    #[serde(default)]
+    pub total_tokens: usize,
+}
+
+impl Usage {
+    pub fn new(prompt_tokens: usize, completion_tokens: usize) -> Self {
+        Self {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        }
+    }
+}
+
+/// Seconds since the Unix epoch of an RFC 3339 timestamp such as
+/// `2024-06-01T10:20:30.123456+02:00`, as returned by Ollama.
+pub(crate) fn unix_timestamp(timestamp: &str) -> Option<u64> {
+    let (date, time) = timestamp.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+
+    let offset_at = time.find(['Z', 'z', '+', '-'])?;
+    let (time, offset) = time.split_at(offset_at);
+    let mut time = time.splitn(3, ':');
+    let hours: i64 = time.next()?.parse().ok()?;
+    let minutes: i64 = time.next()?.parse().ok()?;
+    let seconds: f64 = time.next()?.parse().ok()?;
+    let offset = match offset.chars().next() {
+        Some(sign @ ('+' | '-')) => {
+            let (offset_hours, offset_minutes) = offset[1..].split_once(':')?;
+            let offset =
+                offset_hours.parse::<i64>().ok()? * 3600 + offset_minutes.parse::<i64>().ok()? * 60;
+            match sign {
+                '-' => -offset,
+                _ => offset,
+            }
+        }
+        _ => 0,
+    };
+
+    // Days since the epoch in the proleptic Gregorian calendar.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds = days * 86_400 + hours * 3600 + minutes * 60 + seconds as i64 - offset;
+    u64::try_from(seconds).ok()
+}
+
+fn string_or_list<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrList {
+        String(String),
+        List(Vec<String>),
+    }
+
+    Ok(
+        Option::<StringOrList>::deserialize(deserializer)?.map(|stop| match stop {
+            StringOrList::String(stop) => vec![stop],
+            StringOrList::List(stop) => stop,
+        }),
+    )
+}
+
+/// Deserialize `null` as the default value.
+pub(crate) fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
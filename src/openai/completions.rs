@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+use tokio_stream::{Stream, StreamExt};
+
+use super::{
+    common::{SamplingParams, Usage},
+    sse::sse_stream,
+};
+use crate::{
+    completion::request::CompletionRequest as OllamaCompletionRequest,
+    errors::OllamaError,
+    response::{OllamaStream, StreamHandler},
+};
+
+/// Request of `/v1/completions`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Builder)]
+pub struct CompletionRequest {
+    #[builder(setter(into))]
+    pub model: String,
+
+    #[builder(setter(into))]
+    pub prompt: String,
+
+    #[builder(default)]
+    #[serde(flatten)]
+    pub sampling: SamplingParams,
+
+    /// Text following the completion.
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+
+    /// Set by [`crate::openai::OpenAiClient`] depending on the method called.
+    #[builder(setter(strip_option), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+}
+
+/// The system message, template and context have no OpenAI equivalent and are dropped.
+impl From<OllamaCompletionRequest> for CompletionRequest {
+    fn from(value: OllamaCompletionRequest) -> Self {
+        Self {
+            sampling: SamplingParams::from(&value.options),
+            model: value.model,
+            prompt: value.prompt,
+            suffix: None,
+            stream: value.stream,
+        }
+    }
+}
+
+/// Fails if the sampling parameters have no Ollama equivalent.
+impl TryFrom<CompletionRequest> for OllamaCompletionRequest {
+    type Error = OllamaError;
+
+    fn try_from(value: CompletionRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            options: (&value.sampling).try_into()?,
+            model: value.model,
+            prompt: value.prompt,
+            stream: value.stream,
+            ..Default::default()
+        })
+    }
+}
+
+/// Response of `/v1/completions`, also used for the streamed chunks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Completion {
+    pub id: String,
+
+    pub object: String,
+
+    /// Unix timestamp in seconds.
+    pub created: u64,
+
+    pub model: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
+
+    pub choices: Vec<CompletionChoice>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+impl Completion {
+    /// The text of the first choice.
+    pub fn text(&self) -> &str {
+        self.choices
+            .first()
+            .map(|choice| choice.text.as_str())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompletionChoice {
+    pub text: String,
+
+    pub index: usize,
+
+    /// Why generation stopped, `stop` or `length`.
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+#[async_trait]
+impl StreamHandler for Completion {
+    async fn adapt_stream(
+        input: impl Stream<Item = Result<Bytes, reqwest::Error>> + Unpin + Send + Sync + 'static,
+    ) -> OllamaStream<Self> {
+        sse_stream(input)
+    }
+
+    /// Merge the chunks into one whose first choice holds the whole text.
+    async fn stream_to_response(
+        input: impl Stream<Item = Result<Bytes, reqwest::Error>> + Unpin + Send + Sync + 'static,
+    ) -> Result<Self, OllamaError> {
+        let mut adapted_stream = Self::adapt_stream(input).await;
+        let mut merged: Option<Self> = None;
+        let mut text = String::default();
+        while let Some(item) = adapted_stream.next().await {
+            let item = item?;
+            text += item.text();
+            merged = match merged {
+                None => Some(item),
+                Some(mut merged) => {
+                    let finish_reason = item.choices.first().and_then(|c| c.finish_reason.clone());
+                    if let Some(choice) = merged.choices.first_mut() {
+                        choice.finish_reason = finish_reason.or(choice.finish_reason.take());
+                    }
+                    merged.usage = item.usage.or(merged.usage);
+                    Some(merged)
+                }
+            };
+        }
+
+        let mut merged =
+            merged.ok_or_else(|| OllamaError::InvalidResponse(String::from("empty stream")))?;
+        if let Some(choice) = merged.choices.first_mut() {
+            choice.text = text;
+        }
+        Ok(merged)
+    }
+}
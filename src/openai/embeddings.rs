@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use super::common::Usage;
+use crate::model::generate_embeddings::{
+    EmbeddingsRequest as OllamaEmbeddingsRequest, EmbeddingsResponse as OllamaEmbeddingsResponse,
+};
+
+/// Request of `/v1/embeddings`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingsRequest {
+    pub model: String,
+
+    pub input: EmbeddingInput,
+}
+
+/// The texts to embed, one string or a list of strings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EmbeddingInput {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl EmbeddingInput {
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            Self::One(input) => vec![input],
+            Self::Many(inputs) => inputs,
+        }
+    }
+}
+
+impl From<OllamaEmbeddingsRequest> for EmbeddingsRequest {
+    fn from(value: OllamaEmbeddingsRequest) -> Self {
+        Self {
+            model: value.model,
+            input: EmbeddingInput::One(value.prompt),
+        }
+    }
+}
+
+/// Response of `/v1/embeddings`, one embedding per input.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingList {
+    pub object: String,
+
+    pub data: Vec<Embedding>,
+
+    pub model: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Embedding {
+    pub object: String,
+
+    pub embedding: Vec<f64>,
+
+    /// Position of the embedded input in the request.
+    pub index: usize,
+}
+
+impl From<Embedding> for OllamaEmbeddingsResponse {
+    fn from(value: Embedding) -> Self {
+        Self {
+            embedding: value.embedding,
+        }
+    }
+}
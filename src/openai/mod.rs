@@ -0,0 +1,22 @@
+pub mod chat;
+pub mod client;
+pub mod common;
+pub mod completions;
+pub mod embeddings;
+pub mod models;
+#[cfg(feature = "openai-proxy")]
+pub mod proxy;
+mod sse;
+
+// test module
+mod test_openai;
+
+pub use chat::{
+    ChatCompletion, ChatCompletionChunk, ChatCompletionRequest, ChatCompletionRequestBuilder,
+    ChatMessage, ContentPart, MessageContent,
+};
+pub use client::OpenAiClient;
+pub use common::{ResponseFormat, SamplingParams, Usage};
+pub use completions::{Completion, CompletionRequest, CompletionRequestBuilder};
+pub use embeddings::{EmbeddingInput, EmbeddingList, EmbeddingsRequest};
+pub use models::{Model, ModelList};
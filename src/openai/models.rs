@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use super::common::unix_timestamp;
+use crate::model::list_local::{ListLocalModelsResponse, LocalModel};
+
+/// Response of `/v1/models`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelList {
+    pub object: String,
+
+    pub data: Vec<Model>,
+}
+
+/// Response of `/v1/models/{model}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Model {
+    /// The model name.
+    pub id: String,
+
+    pub object: String,
+
+    /// Unix timestamp in seconds.
+    pub created: u64,
+
+    pub owned_by: String,
+}
+
+impl From<ListLocalModelsResponse> for ModelList {
+    fn from(value: ListLocalModelsResponse) -> Self {
+        Self {
+            object: String::from("list"),
+            data: value.models.into_iter().map(Model::from).collect(),
+        }
+    }
+}
+
+impl From<LocalModel> for Model {
+    fn from(value: LocalModel) -> Self {
+        let owned_by = match value.name.split_once('/') {
+            Some((namespace, _)) => namespace.to_string(),
+            None => String::from("library"),
+        };
+
+        Self {
+            created: unix_timestamp(&value.modified_at).unwrap_or_default(),
+            id: value.name,
+            object: String::from("model"),
+            owned_by,
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_stream::stream;
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use tokio_stream::StreamExt;
+
+use super::{
+    chat::{ChatCompletion, ChatCompletionChunk, ChatCompletionRequest},
+    embeddings::{Embedding, EmbeddingList, EmbeddingsRequest},
+    models::ModelList,
+    sse::DONE,
+};
+use crate::{
+    api::OllamaApi, chat_completion::request::ChatCompletionRequest as OllamaChatRequest,
+    errors::OllamaError, model::generate_embeddings::EmbeddingsRequest as OllamaEmbeddingsRequest,
+};
+
+type Api = Arc<dyn OllamaApi>;
+
+/// Serve `/v1/chat/completions`, `/v1/embeddings` and `/v1/models` by translating
+/// OpenAI requests into calls to `api` and its responses back.
+pub fn router(api: impl OllamaApi + 'static) -> Router {
+    let api: Api = Arc::new(api);
+    Router::new()
+        .route("/v1/chat/completions", post(chat_completions))
+        .route("/v1/embeddings", post(embeddings))
+        .route("/v1/models", get(models))
+        .with_state(api)
+}
+
+async fn chat_completions(
+    State(api): State<Api>,
+    request: Result<Json<ChatCompletionRequest>, JsonRejection>,
+) -> Result<Response, ApiError> {
+    let Json(request) = request?;
+    let streaming = request.stream.unwrap_or_default();
+
+    // Always stream from Ollama, the chunks are merged for non-streaming requests.
+    let mut native = OllamaChatRequest::try_from(request)?;
+    native.stream = None;
+    let response = api.chat(native).await?;
+
+    let id = completion_id("chatcmpl");
+    let created = now();
+    if !streaming {
+        let response = response.as_response().await?;
+        return Ok(Json(ChatCompletion::from_ollama(id, created, response)).into_response());
+    }
+
+    let mut chunks = response.as_stream().await?;
+    let events = stream! {
+        while let Some(item) = chunks.next().await {
+            match item {
+                Ok(chunk) => {
+                    let chunk = ChatCompletionChunk::from_ollama(&id, created, chunk);
+                    yield Ok::<_, Infallible>(Event::default().data(json!(chunk).to_string()));
+                }
+                Err(e) => {
+                    let error = ApiError::from(e);
+                    yield Ok(Event::default().data(error.body().to_string()));
+                    break;
+                }
+            }
+        }
+        yield Ok(Event::default().data(DONE));
+    };
+    Ok(Sse::new(events).into_response())
+}
+
+async fn embeddings(
+    State(api): State<Api>,
+    request: Result<Json<EmbeddingsRequest>, JsonRejection>,
+) -> Result<Json<EmbeddingList>, ApiError> {
+    let Json(request) = request?;
+
+    let mut data = vec![];
+    for (index, input) in request.input.into_vec().into_iter().enumerate() {
+        let native = OllamaEmbeddingsRequest {
+            model: request.model.clone(),
+            prompt: input,
+            ..Default::default()
+        };
+        let response = api.generate_embeddings(native).await?;
+        data.push(Embedding {
+            object: String::from("embedding"),
+            embedding: response.embedding,
+            index,
+        });
+    }
+
+    Ok(Json(EmbeddingList {
+        object: String::from("list"),
+        data,
+        model: request.model,
+        usage: None,
+    }))
+}
+
+async fn models(State(api): State<Api>) -> Result<Json<ModelList>, ApiError> {
+    Ok(Json(api.list_local().await?.into()))
+}
+
+/// An error in the OpenAI format: `{"error": {"message": ..., "type": ...}}`.
+struct ApiError {
+    status: StatusCode,
+    kind: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn body(&self) -> Value {
+        json!({
+            "error": {
+                "message": self.message,
+                "type": self.kind,
+                "param": null,
+                "code": null,
+            }
+        })
+    }
+}
+
+impl From<OllamaError> for ApiError {
+    fn from(value: OllamaError) -> Self {
+        let (status, kind) = match &value {
+            e if e.is_model_not_found() => (StatusCode::NOT_FOUND, "invalid_request_error"),
+            OllamaError::InvalidParameter(_) => (StatusCode::BAD_REQUEST, "invalid_request_error"),
+            OllamaError::RequestError(_) | OllamaError::StreamError(_) => {
+                (StatusCode::BAD_GATEWAY, "api_error")
+            }
+            OllamaError::Timeout(_) => (StatusCode::GATEWAY_TIMEOUT, "api_error"),
+            OllamaError::CircuitOpen(_) => (StatusCode::SERVICE_UNAVAILABLE, "api_error"),
+            OllamaError::QueueFull(_) => (StatusCode::TOO_MANY_REQUESTS, "rate_limit_error"),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "api_error"),
+        };
+
+        // Ollama errors hold the body returned by the server: `{"error": message}`.
+        let message = match value {
+            OllamaError::OllamaError(body) | OllamaError::ServerError(body) => {
+                serde_json::from_str::<Value>(&body)
+                    .ok()
+                    .and_then(|body| body.get("error")?.as_str().map(String::from))
+                    .unwrap_or(body)
+            }
+            e => e.to_string(),
+        };
+
+        Self {
+            status,
+            kind,
+            message,
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(value: JsonRejection) -> Self {
+        Self {
+            status: value.status(),
+            kind: "invalid_request_error",
+            message: value.body_text(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body())).into_response()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn completion_id(prefix: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    format!(
+        "{prefix}-{:x}",
+        nanos ^ COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
@@ -0,0 +1,88 @@
+use async_stream::stream;
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::{errors::OllamaError, response::OllamaStream};
+
+/// Payload ending an OpenAI event stream.
+pub(crate) const DONE: &str = "[DONE]";
+
+/// Decode a `text/event-stream` body into the JSON objects carried by its `data:`
+/// fields, until the `[DONE]` sentinel. Events may be split across chunks.
+pub(crate) fn sse_stream<T>(
+    mut input: impl Stream<Item = Result<Bytes, reqwest::Error>> + Unpin + Send + Sync + 'static,
+) -> OllamaStream<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let events = stream! {
+        let mut buffer = vec![];
+        let mut data = String::default();
+        let mut ended = false;
+
+        while !ended {
+            match input.next().await {
+                Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                Some(Err(e)) => {
+                    yield Err(OllamaError::StreamError(e.to_string()));
+                    return;
+                }
+                // A final event may not be followed by a blank line.
+                None => {
+                    buffer.extend_from_slice(b"\n\n");
+                    ended = true;
+                }
+            }
+
+            while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line);
+                let Some(event) = feed_line(line.trim_end_matches(['\r', '\n']), &mut data) else {
+                    continue;
+                };
+                if event == DONE {
+                    return;
+                }
+                yield parse_event(&event);
+            }
+        }
+    };
+
+    Box::pin(events)
+}
+
+/// Add one line to the `data` of the current event, returning the event's data
+/// once the blank line ending it is reached.
+fn feed_line(line: &str, data: &mut String) -> Option<String> {
+    if line.is_empty() {
+        return match data.is_empty() {
+            true => None,
+            false => Some(std::mem::take(data)),
+        };
+    }
+
+    if let Some(value) = line.strip_prefix("data:") {
+        if !data.is_empty() {
+            data.push('\n');
+        }
+        data.push_str(value.strip_prefix(' ').unwrap_or(value));
+    }
+    // Comments, `event:`, `id:` and `retry:` fields carry nothing we use.
+    None
+}
+
+/// Parse the data of an event, which may be an error object sent mid-stream.
+fn parse_event<T: DeserializeOwned>(data: &str) -> Result<T, OllamaError> {
+    let value: Value =
+        serde_json::from_str(data).map_err(|e| OllamaError::InvalidResponse(e.to_string()))?;
+    if let Some(error) = value.get("error") {
+        let err_msg = match error.get("message").and_then(Value::as_str) {
+            Some(message) => message.to_string(),
+            None => error.to_string(),
+        };
+        return Err(OllamaError::OllamaError(err_msg));
+    }
+    serde_json::from_value(value).map_err(|e| OllamaError::InvalidResponse(e.to_string()))
+}
@@ -0,0 +1,422 @@
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use serde_json::json;
+    use tokio_stream::{iter, StreamExt};
+
+    use crate::{
+        chat_completion::{
+            message::{Message, Role},
+            request::{
+                ChatCompletionRequest as OllamaChatRequest,
+                ChatCompletionRequestBuilder as OllamaChatRequestBuilder,
+            },
+            response::ChatResponse,
+        },
+        errors::OllamaError,
+        openai::{
+            sse::sse_stream, ChatCompletion, ChatCompletionChunk, ChatCompletionRequest,
+            ChatCompletionRequestBuilder, ChatMessage, Completion, CompletionRequestBuilder,
+            ContentPart, MessageContent, OpenAiClient, ResponseFormat,
+        },
+        options::OptionsConstructor,
+        testing::{MockOllama, MockResponse},
+    };
+
+    fn chunk_event(content: &str, finish_reason: Option<&str>) -> String {
+        let chunk = json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion.chunk",
+            "created": 1717200000,
+            "model": "llama3",
+            "choices": [{
+                "index": 0,
+                "delta": { "role": "assistant", "content": content },
+                "finish_reason": finish_reason,
+            }],
+        });
+        format!("data: {chunk}\n\n")
+    }
+
+    fn user(content: &str) -> ChatMessage {
+        ChatMessage {
+            role: Role::User,
+            content: MessageContent::Text(content.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sse_split_events() {
+        let body = chunk_event("Hel", None) + &chunk_event("lo", Some("stop")) + "data: [DONE]\n\n";
+        // Split the body in the middle of events and lines.
+        let chunks: Vec<Result<Bytes, reqwest::Error>> = body
+            .as_bytes()
+            .chunks(7)
+            .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+            .collect();
+
+        let stream = sse_stream::<ChatCompletionChunk>(iter(chunks));
+        let chunks: Vec<_> = stream.map(Result::unwrap).collect().await;
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].content(), "Hel");
+        assert_eq!(chunks[1].choices[0].finish_reason.as_deref(), Some("stop"));
+    }
+
+    #[tokio::test]
+    async fn test_sse_error_event() {
+        let body = ": keep-alive\n\ndata: {\"error\":{\"message\":\"model not found\"}}\n\n";
+        let chunks = vec![Ok::<_, reqwest::Error>(Bytes::from(body))];
+
+        let mut stream = sse_stream::<ChatCompletionChunk>(iter(chunks));
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            err,
+            OllamaError::OllamaError(String::from("model not found"))
+        );
+        assert!(stream.next().await.is_none());
+    }
+
+    #[test]
+    fn test_message_mapping() {
+        let message = Message {
+            role: Role::User,
+            content: String::from("What is this?"),
+            images: Some(vec![String::from("aGVsbG8=")]),
+        };
+
+        let converted = ChatMessage::from(message.clone());
+        assert_eq!(
+            converted.content,
+            MessageContent::Parts(vec![
+                ContentPart::Text {
+                    text: String::from("What is this?")
+                },
+                ContentPart::ImageUrl {
+                    image_url: crate::openai::chat::ImageUrl {
+                        url: String::from("data:image/png;base64,aGVsbG8=")
+                    }
+                },
+            ])
+        );
+        assert_eq!(Message::from(converted), message);
+
+        let null_content: ChatMessage =
+            serde_json::from_str("{\"role\":\"assistant\",\"content\":null}").unwrap();
+        assert_eq!(null_content.content.text(), "");
+    }
+
+    #[test]
+    fn test_request_mapping() {
+        let request = OllamaChatRequestBuilder::default()
+            .model("llama3")
+            .messages(vec![Message {
+                role: Role::User,
+                content: String::from("Hi"),
+                images: None,
+            }])
+            .temperature(0.5)
+            .num_predict(32)
+            .stop("###")
+            .format(crate::format::Format::JSON)
+            .build()
+            .unwrap();
+
+        let converted = ChatCompletionRequest::from(request.clone());
+        assert_eq!(
+            serde_json::to_value(&converted).unwrap(),
+            json!({
+                "model": "llama3",
+                "messages": [{ "role": "user", "content": "Hi" }],
+                "temperature": 0.5,
+                "max_tokens": 32,
+                "stop": ["###"],
+                "response_format": { "type": "json_object" },
+            })
+        );
+        assert_eq!(OllamaChatRequest::try_from(converted).unwrap(), request);
+
+        let single_stop: ChatCompletionRequest = serde_json::from_value(json!({
+            "model": "llama3",
+            "messages": [],
+            "stop": "###",
+            "response_format": { "type": "text" },
+        }))
+        .unwrap();
+        assert_eq!(single_stop.sampling.stop, Some(vec![String::from("###")]));
+        assert_eq!(single_stop.response_format, Some(ResponseFormat::Text));
+    }
+
+    #[test]
+    fn test_sampling_mapping() {
+        let request: ChatCompletionRequest = serde_json::from_value(json!({
+            "model": "llama3",
+            "messages": [],
+            "frequency_penalty": 0.5,
+            "presence_penalty": 1.5,
+        }))
+        .unwrap();
+        let native = OllamaChatRequest::try_from(request).unwrap();
+        let options = &native.options;
+        assert_eq!(options.frequency_penalty, Some(0.5));
+        assert_eq!(options.presence_penalty, Some(1.5));
+        let converted = ChatCompletionRequest::from(native);
+        assert_eq!(converted.sampling.frequency_penalty, Some(0.5));
+        assert_eq!(converted.sampling.presence_penalty, Some(1.5));
+
+        let several_stops: ChatCompletionRequest = serde_json::from_value(json!({
+            "model": "llama3",
+            "messages": [],
+            "stop": ["###", "END"],
+        }))
+        .unwrap();
+        assert!(matches!(
+            OllamaChatRequest::try_from(several_stops),
+            Err(OllamaError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_finish_reason() {
+        let response: ChatResponse = serde_json::from_value(json!({
+            "model": "llama3",
+            "created_at": "2024-06-01T00:00:00Z",
+            "message": { "role": "assistant", "content": "Once upon" },
+            "done": true,
+            "done_reason": "length",
+        }))
+        .unwrap();
+        let completion = ChatCompletion::from_ollama("chatcmpl-1", 0, response.clone());
+        assert_eq!(
+            completion.choices[0].finish_reason.as_deref(),
+            Some("length")
+        );
+        let chunk = ChatCompletionChunk::from_ollama("chatcmpl-1", 0, response.clone());
+        assert_eq!(chunk.choices[0].finish_reason.as_deref(), Some("length"));
+
+        let stopped = ChatResponse {
+            done_reason: Some(String::from("stop")),
+            ..response
+        };
+        let completion = ChatCompletion::from_ollama("chatcmpl-1", 0, stopped);
+        assert_eq!(completion.choices[0].finish_reason.as_deref(), Some("stop"));
+    }
+
+    #[tokio::test]
+    async fn test_chat_stream_mock() {
+        let mock = MockOllama::start().await;
+        let body =
+            chunk_event("Hello", None) + &chunk_event(" world", Some("stop")) + "data: [DONE]\n\n";
+        mock.respond("/v1/chat/completions", MockResponse::text(body));
+
+        let request = ChatCompletionRequestBuilder::default()
+            .model("llama3")
+            .messages(vec![user("Hi")])
+            .build()
+            .unwrap();
+        let client = OpenAiClient::from(mock.client());
+        let stream = client.chat_stream(request).await.unwrap();
+        let content: Vec<String> = stream
+            .map(|chunk| chunk.unwrap().content().to_string())
+            .collect()
+            .await;
+        assert_eq!(content.concat(), "Hello world");
+
+        let received = mock.requests_to("/v1/chat/completions");
+        assert_eq!(received[0].body.as_ref().unwrap()["stream"], json!(true));
+    }
+
+    #[tokio::test]
+    async fn test_chat_mock() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/v1/chat/completions",
+            MockResponse::json(json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion",
+                "created": 1717200000,
+                "model": "llama3",
+                "choices": [{
+                    "index": 0,
+                    "message": { "role": "assistant", "content": "Hello" },
+                    "finish_reason": "stop",
+                }],
+                "usage": { "prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6 },
+            })),
+        );
+        mock.respond(
+            "/v1/chat/completions",
+            MockResponse::Json {
+                status: 404,
+                body: json!({ "error": { "message": "model \"nope\" not found" } }),
+            },
+        );
+
+        let client = OpenAiClient::from(mock.client());
+        let request = ChatCompletionRequestBuilder::default()
+            .model("llama3")
+            .messages(vec![user("Hi")])
+            .build()
+            .unwrap();
+        let completion = client.chat(request.clone()).await.unwrap();
+        assert_eq!(completion.message().unwrap().content, "Hello");
+        assert_eq!(completion.usage.unwrap().total_tokens, 6);
+
+        let err = client.chat(request).await.unwrap_err();
+        assert!(matches!(err, OllamaError::OllamaError(msg) if msg.contains("not found")));
+    }
+
+    #[tokio::test]
+    async fn test_completion_and_models_mock() {
+        let mock = MockOllama::start().await;
+        let chunk = |text: &str| {
+            let completion = json!({
+                "id": "cmpl-1",
+                "object": "text_completion",
+                "created": 1717200000,
+                "model": "llama3",
+                "choices": [{ "text": text, "index": 0, "finish_reason": null }],
+            });
+            format!("data: {completion}\n\n")
+        };
+        mock.respond(
+            "/v1/completions",
+            MockResponse::text(chunk("1, 2") + &chunk(", 3") + "data: [DONE]\n\n"),
+        );
+        mock.respond(
+            "/v1/models",
+            MockResponse::json(json!({
+                "object": "list",
+                "data": [{ "id": "llama3:latest", "object": "model", "created": 1717200000, "owned_by": "library" }],
+            })),
+        );
+
+        let client = OpenAiClient::from(mock.client());
+        let request = CompletionRequestBuilder::default()
+            .model("llama3")
+            .prompt("Count:")
+            .build()
+            .unwrap();
+        let texts: Vec<Completion> = client
+            .completion_stream(request)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        let text: String = texts.iter().map(Completion::text).collect();
+        assert_eq!(text, "1, 2, 3");
+
+        let models = client.models().await.unwrap();
+        assert_eq!(models.data[0].id, "llama3:latest");
+    }
+
+    #[test]
+    fn test_unix_timestamp() {
+        use crate::openai::common::unix_timestamp;
+
+        assert_eq!(unix_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            unix_timestamp("2024-06-01T00:00:00.000000Z"),
+            Some(1717200000)
+        );
+        assert_eq!(
+            unix_timestamp("2024-06-01T02:30:00.123456789+02:30"),
+            Some(1717200000)
+        );
+        assert_eq!(
+            unix_timestamp("2024-05-31T22:00:00-02:00"),
+            Some(1717200000)
+        );
+        assert_eq!(unix_timestamp("yesterday"), None);
+    }
+
+    #[cfg(feature = "openai-proxy")]
+    async fn start_proxy(mock: &MockOllama) -> OpenAiClient {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = crate::openai::proxy::router(mock.client());
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        OpenAiClient::new(format!("http://{addr}"))
+    }
+
+    #[cfg(feature = "openai-proxy")]
+    #[tokio::test]
+    async fn test_proxy_chat() {
+        let mock = MockOllama::start().await;
+        let client = start_proxy(&mock).await;
+
+        let request = ChatCompletionRequestBuilder::default()
+            .model("llama3")
+            .messages(vec![user("Hi")])
+            .sampling(crate::openai::SamplingParams {
+                temperature: Some(0.5),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        let completion = client.chat(request.clone()).await.unwrap();
+        assert_eq!(completion.object, "chat.completion");
+        assert_eq!(completion.message().unwrap().content, "Hello from mock");
+        assert_eq!(completion.usage, Some(crate::openai::Usage::new(10, 3)));
+
+        let chunks: Vec<ChatCompletionChunk> = client
+            .chat_stream(request)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        let content: String = chunks.iter().map(ChatCompletionChunk::content).collect();
+        assert_eq!(content, "Hello from mock");
+        let last = chunks.last().unwrap();
+        assert_eq!(last.choices[0].finish_reason.as_deref(), Some("stop"));
+        assert_eq!(last.usage, Some(crate::openai::Usage::new(10, 3)));
+
+        let received = mock.requests_to("/api/chat");
+        assert_eq!(received.len(), 2);
+        assert_eq!(
+            received[0].body,
+            Some(json!({
+                "model": "llama3",
+                "messages": [{ "role": "user", "content": "Hi" }],
+                "options": { "temperature": 0.5 },
+            }))
+        );
+    }
+
+    #[cfg(feature = "openai-proxy")]
+    #[tokio::test]
+    async fn test_proxy_embeddings_and_errors() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/chat",
+            MockResponse::error(404, "model 'nope' not found, try pulling it first"),
+        );
+        let client = start_proxy(&mock).await;
+
+        let request = crate::openai::EmbeddingsRequest {
+            model: String::from("nomic-embed-text"),
+            input: crate::openai::EmbeddingInput::Many(vec![String::from("a"), String::from("b")]),
+        };
+        let embeddings = client.embeddings(request).await.unwrap();
+        assert_eq!(embeddings.data.len(), 2);
+        assert_eq!(embeddings.data[1].index, 1);
+        assert_eq!(embeddings.data[1].embedding, vec![0.1, 0.2, 0.3]);
+        assert_eq!(embeddings.usage, None);
+
+        let request = ChatCompletionRequestBuilder::default()
+            .model("nope")
+            .messages(vec![user("Hi")])
+            .build()
+            .unwrap();
+        let err = client.chat(request).await.unwrap_err();
+        let OllamaError::OllamaError(body) = err else {
+            panic!("unexpected error: {err:?}");
+        };
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            body["error"]["message"],
+            json!("model 'nope' not found, try pulling it first")
+        );
+    }
+}
@@ -1,10 +1,18 @@
+use std::cell::Cell;
+
 use derive_builder::Builder;
-use serde::Serialize;
+use serde::{
+    de::{self, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer, Serialize,
+};
+use serde_json::{Map, Value};
+
+use crate::errors::OllamaError;
 
 /// Ollama API Doc
 /// https://github.com/ollama/ollama/blob/main/docs/modelfile.md#valid-parameters-and-values
 
-#[derive(Debug, Clone, Default, Builder, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Builder, Serialize, Deserialize, PartialEq)]
 #[builder(derive(PartialEq))]
 pub struct Options {
     /// Enable Mirostat sampling for controlling perplexity.
@@ -48,12 +56,26 @@ pub struct Options {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub repeat_penalty: Option<f32>,
 
+    /// Penalizes tokens which already appeared in the text, whatever their
+    /// number of occurrences, making new topics more likely.
+    /// (Default: 0)
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+
+    /// Penalizes tokens in proportion to their number of occurrences in the
+    /// text, making verbatim repetitions less likely.
+    /// (Default: 0)
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+
     /// The temperature of the model. Increasing the temperature will make
     /// the model answer more creatively.
     /// (Default: 0.8)
     #[builder(setter(strip_option), default)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub temperature: Option<i32>,
+    pub temperature: Option<f32>,
 
     /// Sets the random number seed to use for generation. Setting this to a
     /// specific number will make the model generate the same text for the same prompt.
@@ -102,6 +124,70 @@ impl Options {
     pub fn is_default(&self) -> bool {
         self == &Options::default()
     }
+
+    /// Names of the parameters, in declaration order, as serialized.
+    pub fn names() -> &'static [&'static str] {
+        let names = Cell::new(&[][..]);
+        let _ = Options::deserialize(FieldNames(&names));
+        names.get()
+    }
+
+    /// The value of the parameter called `name`, if it is set.
+    pub fn get(&self, name: &str) -> Option<Value> {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|mut params| params.get_mut(name).map(Value::take))
+    }
+
+    /// Set the parameter called `name`, one of [`Options::names`], from its textual
+    /// value, e.g. `("top_p", "0.9")`. The value is parsed as JSON, or taken as a
+    /// string if it isn't valid JSON, and `null` unsets the parameter. The options
+    /// are left unchanged on error.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), OllamaError> {
+        if !Self::names().contains(&name) {
+            return Err(OllamaError::InvalidParameter(format!(
+                "unknown option: {name}"
+            )));
+        }
+
+        let value = serde_json::from_str(value).unwrap_or(Value::String(value.to_string()));
+        let mut params = match serde_json::to_value(&*self) {
+            Ok(Value::Object(params)) => params,
+            _ => Map::new(),
+        };
+        params.insert(name.to_string(), value);
+
+        *self = serde_json::from_value(Value::Object(params))
+            .map_err(|e| OllamaError::InvalidParameter(format!("{name}: {e}")))?;
+        Ok(())
+    }
+}
+
+/// A deserializer which only records the field names of the struct it is asked for.
+struct FieldNames<'a>(&'a Cell<&'static [&'static str]>);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.set(fields);
+        Err(de::Error::custom("field names only"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
 }
 
 impl OptionsBuilder {
@@ -145,7 +231,19 @@ pub trait OptionsConstructor: GetOptionsBuilder {
         self
     }
 
-    fn temperature(&mut self, temperature: i32) -> &mut Self {
+    fn presence_penalty(&mut self, presence_penalty: f32) -> &mut Self {
+        self.get_options_builder()
+            .presence_penalty(presence_penalty);
+        self
+    }
+
+    fn frequency_penalty(&mut self, frequency_penalty: f32) -> &mut Self {
+        self.get_options_builder()
+            .frequency_penalty(frequency_penalty);
+        self
+    }
+
+    fn temperature(&mut self, temperature: f32) -> &mut Self {
         self.get_options_builder().temperature(temperature);
         self
     }
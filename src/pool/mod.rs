@@ -0,0 +1,6 @@
+pub mod pool;
+
+// test module
+mod test_pool;
+
+pub use pool::{NodeStatus, OllamaPool, DEFAULT_HEALTH_CHECK_INTERVAL};
@@ -0,0 +1,508 @@
+use std::{
+    collections::HashSet,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use tokio::time::{timeout, Instant};
+
+use crate::{
+    api::OllamaApi,
+    chat_completion::{request::ChatCompletionRequest, response::ChatResponse},
+    client::{CircuitState, OllamaClient},
+    completion::{request::CompletionRequest, response::CompletionResponse},
+    control::RequestControl,
+    errors::OllamaError,
+    model::{
+        canonical_name,
+        copy::CopyModelRequest,
+        create::{CreateModelRequest, CreateModelResponse},
+        delete::DeleteModelRequest,
+        generate_embeddings::{EmbeddingsRequest, EmbeddingsResponse},
+        list_local::ListLocalModelsResponse,
+        list_running::ListRunningModelsResponse,
+        pull::{PullModelRequest, PullModelResponse},
+        push::{PushModelRequest, PushModelResponse},
+        show_info::{ShowModelRequest, ShowModelResponse},
+    },
+    response::OllamaResponse,
+};
+
+/// Default interval between two health checks of the nodes of a pool.
+pub const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Maximum duration of the health check of one node.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Health and load of one node of an [`OllamaPool`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeStatus {
+    /// Base URL of the node.
+    pub host: String,
+
+    /// False once the node failed a health check or could not be reached.
+    pub healthy: bool,
+
+    /// Models loaded into memory, as of the last health check or request.
+    pub loaded_models: Vec<String>,
+
+    /// Requests sent to the node through the pool which haven't completed yet.
+    pub in_flight: usize,
+
+    /// State of the circuit breaker of the node's client, if it has one.
+    pub circuit: Option<CircuitState>,
+}
+
+#[derive(Debug)]
+struct Node {
+    client: OllamaClient,
+    state: Mutex<NodeState>,
+    in_flight: Arc<AtomicUsize>,
+}
+
+#[derive(Debug)]
+struct NodeState {
+    healthy: bool,
+    loaded_models: Vec<String>,
+}
+
+impl Node {
+    fn new(client: OllamaClient) -> Self {
+        Self {
+            client,
+            state: Mutex::new(NodeState {
+                healthy: true,
+                loaded_models: vec![],
+            }),
+            in_flight: Arc::default(),
+        }
+    }
+
+    fn status(&self) -> NodeStatus {
+        let state = self.state.lock().unwrap();
+        NodeStatus {
+            host: self.client.host().to_string(),
+            healthy: state.healthy,
+            loaded_models: state.loaded_models.clone(),
+            in_flight: self.in_flight.load(Ordering::SeqCst),
+            circuit: self.client.circuit_breaker().map(|breaker| breaker.state()),
+        }
+    }
+
+    fn has_loaded(&self, model: &str) -> bool {
+        let model = canonical_name(model);
+        let state = self.state.lock().unwrap();
+        state.loaded_models.contains(&model)
+    }
+
+    fn mark_loaded(&self, model: &str) {
+        let model = canonical_name(model);
+        let mut state = self.state.lock().unwrap();
+        state.healthy = true;
+        if !state.loaded_models.contains(&model) {
+            state.loaded_models.push(model);
+        }
+    }
+
+    fn mark_unhealthy(&self) {
+        self.state.lock().unwrap().healthy = false;
+    }
+
+    async fn check_health(&self) {
+        let running = timeout(HEALTH_CHECK_TIMEOUT, self.client.list_running()).await;
+        let mut state = self.state.lock().unwrap();
+        match running {
+            Ok(Ok(running)) => {
+                state.healthy = true;
+                state.loaded_models = running
+                    .models
+                    .iter()
+                    .map(|model| canonical_name(&model.name))
+                    .collect();
+            }
+            _ => {
+                state.healthy = false;
+                state.loaded_models.clear();
+            }
+        }
+    }
+}
+
+/// Counts a request as in flight on a node until dropped.
+struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    fn start(counter: &Arc<AtomicUsize>) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter.clone())
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A pool of Ollama servers used through the [`OllamaApi`] trait.
+///
+/// Requests for a model go preferably to a node which already has the model loaded
+/// (as listed by `/api/ps`), then to the node with the fewest requests in flight.
+/// When a node can't be reached or fails with a server error before the first
+/// chunk of the response is received, the request is sent to the next node;
+/// errors caused by the request itself are returned as is. Nodes are
+/// health-checked at most once per interval, before routing a request.
+///
+/// Model management calls (create, copy, delete, pull, push) are sent to a single
+/// node, and create, copy and delete are never sent to another one; use
+/// [`OllamaPool::clients`] to manage every node.
+#[derive(Debug, Clone)]
+pub struct OllamaPool {
+    nodes: Arc<Vec<Node>>,
+    health_check_interval: Duration,
+    last_health_check: Arc<Mutex<Option<Instant>>>,
+}
+
+impl OllamaPool {
+    /// A pool of default clients for the given base URLs.
+    pub fn new(hosts: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self::from_clients(hosts.into_iter().map(OllamaClient::new))
+    }
+
+    /// A pool of preconfigured clients, one per node.
+    pub fn from_clients(clients: impl IntoIterator<Item = OllamaClient>) -> Self {
+        Self {
+            nodes: Arc::new(clients.into_iter().map(Node::new).collect()),
+            health_check_interval: DEFAULT_HEALTH_CHECK_INTERVAL,
+            last_health_check: Arc::default(),
+        }
+    }
+
+    /// Set the minimum interval between two health checks.
+    pub fn with_health_check_interval(mut self, interval: Duration) -> Self {
+        self.health_check_interval = interval;
+        self
+    }
+
+    /// The clients of the nodes, in the order they were given.
+    pub fn clients(&self) -> Vec<OllamaClient> {
+        self.nodes.iter().map(|node| node.client.clone()).collect()
+    }
+
+    /// The status of every node, in the order they were given.
+    pub fn status(&self) -> Vec<NodeStatus> {
+        self.nodes.iter().map(Node::status).collect()
+    }
+
+    /// Check every node now, refreshing its health and the list of its loaded models.
+    pub async fn check_health(&self) {
+        *self.last_health_check.lock().unwrap() = Some(Instant::now());
+        join_all(self.nodes.iter().map(Node::check_health)).await;
+    }
+
+    async fn check_health_if_due(&self) {
+        let due = match *self.last_health_check.lock().unwrap() {
+            Some(last) => last.elapsed() >= self.health_check_interval,
+            None => true,
+        };
+        if due {
+            self.check_health().await;
+        }
+    }
+
+    /// Indexes of the nodes in the order they should be tried for `model`:
+    /// healthy nodes with a closed circuit first, those with the model loaded
+    /// first among them, then the least loaded.
+    fn candidates(&self, model: Option<&str>) -> Vec<usize> {
+        let mut candidates: Vec<_> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| {
+                let status = node.status();
+                let available = status.healthy && status.circuit != Some(CircuitState::Open);
+                let loaded = model.is_some_and(|model| node.has_loaded(model));
+                (!available, !loaded, status.in_flight, index)
+            })
+            .collect();
+        candidates.sort();
+        candidates.into_iter().map(|(.., index)| index).collect()
+    }
+
+    /// Run `call` against the best node for `model`, failing over to the next
+    /// nodes while it fails. The returned guard counts the request as in flight.
+    async fn route<T, F, Fut>(
+        &self,
+        model: Option<&str>,
+        call: F,
+    ) -> Result<(T, InFlight), OllamaError>
+    where
+        F: Fn(OllamaClient) -> Fut,
+        Fut: Future<Output = Result<T, OllamaError>>,
+    {
+        self.dispatch(model, true, call).await
+    }
+
+    /// Run `call` against the best node only, for requests which mustn't be sent
+    /// again to another node once one may have acted on them.
+    async fn route_once<T, F, Fut>(&self, call: F) -> Result<(T, InFlight), OllamaError>
+    where
+        F: Fn(OllamaClient) -> Fut,
+        Fut: Future<Output = Result<T, OllamaError>>,
+    {
+        self.dispatch(None, false, call).await
+    }
+
+    async fn dispatch<T, F, Fut>(
+        &self,
+        model: Option<&str>,
+        fail_over: bool,
+        call: F,
+    ) -> Result<(T, InFlight), OllamaError>
+    where
+        F: Fn(OllamaClient) -> Fut,
+        Fut: Future<Output = Result<T, OllamaError>>,
+    {
+        self.check_health_if_due().await;
+
+        let mut last_error = None;
+        let mut candidates = self.candidates(model);
+        if !fail_over {
+            candidates.truncate(1);
+        }
+        for index in candidates {
+            let node = &self.nodes[index];
+            let in_flight = InFlight::start(&node.in_flight);
+            match call(node.client.clone()).await {
+                Ok(output) => {
+                    if let Some(model) = model {
+                        node.mark_loaded(model);
+                    }
+                    return Ok((output, in_flight));
+                }
+                Err(e) => {
+                    if is_unreachable(&e) {
+                        node.mark_unhealthy();
+                    }
+                    if !should_fail_over(&e) {
+                        return Err(e);
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| OllamaError::RequestError(String::from("the pool has no node"))))
+    }
+
+    /// Run `call` against every healthy node, keeping the successful results.
+    async fn broadcast<T, F, Fut>(&self, call: F) -> Result<Vec<T>, OllamaError>
+    where
+        F: Fn(OllamaClient) -> Fut,
+        Fut: Future<Output = Result<T, OllamaError>>,
+    {
+        self.check_health_if_due().await;
+
+        let nodes: Vec<_> = self
+            .nodes
+            .iter()
+            .filter(|node| node.status().healthy)
+            .collect();
+        let results = join_all(nodes.iter().map(|node| call(node.client.clone()))).await;
+
+        let mut outputs = vec![];
+        let mut last_error = None;
+        for (node, result) in nodes.into_iter().zip(results) {
+            match result {
+                Ok(output) => outputs.push(output),
+                Err(e) => {
+                    if is_unreachable(&e) {
+                        node.mark_unhealthy();
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match (outputs.is_empty(), last_error) {
+            (true, Some(e)) => Err(e),
+            (true, None) => Err(OllamaError::RequestError(String::from(
+                "no healthy node in the pool",
+            ))),
+            _ => Ok(outputs),
+        }
+    }
+}
+
+/// Errors worth trying another node for: the node could not be reached,
+/// failed with a server error, has its circuit open or its queue full. Errors
+/// caused by the request itself, and timeouts set by the caller, would fail the
+/// same way elsewhere.
+fn should_fail_over(err: &OllamaError) -> bool {
+    matches!(
+        err,
+        OllamaError::RequestError(_)
+            | OllamaError::StreamError(_)
+            | OllamaError::ServerError(_)
+            | OllamaError::CircuitOpen(_)
+            | OllamaError::QueueFull(_)
+    )
+}
+
+fn is_unreachable(err: &OllamaError) -> bool {
+    matches!(
+        err,
+        OllamaError::RequestError(_) | OllamaError::StreamError(_)
+    )
+}
+
+#[async_trait]
+impl OllamaApi for OllamaPool {
+    async fn chat_with_control(
+        &self,
+        request: ChatCompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<ChatResponse>, OllamaError> {
+        let model = request.model.clone();
+        let (response, in_flight) = self
+            .route(Some(&model), |client| {
+                let request = request.clone();
+                let control = control.clone();
+                async move { client.chat_with_control(request, control).await }
+            })
+            .await?;
+        Ok(response.hold(in_flight))
+    }
+
+    async fn completion_with_control(
+        &self,
+        request: CompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<CompletionResponse>, OllamaError> {
+        let model = request.model.clone();
+        let (response, in_flight) = self
+            .route(Some(&model), |client| {
+                let request = request.clone();
+                let control = control.clone();
+                async move { client.completion_with_control(request, control).await }
+            })
+            .await?;
+        Ok(response.hold(in_flight))
+    }
+
+    async fn generate_embeddings(
+        &self,
+        request: EmbeddingsRequest,
+    ) -> Result<EmbeddingsResponse, OllamaError> {
+        let model = request.model.clone();
+        let (response, _) = self
+            .route(Some(&model), |client| {
+                let request = request.clone();
+                async move { client.generate_embeddings(request).await }
+            })
+            .await?;
+        Ok(response)
+    }
+
+    async fn create(
+        &self,
+        request: CreateModelRequest,
+    ) -> Result<OllamaResponse<CreateModelResponse>, OllamaError> {
+        let (response, in_flight) = self
+            .route_once(|client| {
+                let request = request.clone();
+                async move { client.create(request).await }
+            })
+            .await?;
+        Ok(response.hold(in_flight))
+    }
+
+    /// The models available on any healthy node.
+    async fn list_local(&self) -> Result<ListLocalModelsResponse, OllamaError> {
+        let responses = self
+            .broadcast(|client| async move { client.list_local().await })
+            .await?;
+
+        let mut names = HashSet::new();
+        let models = responses
+            .into_iter()
+            .flat_map(|response| response.models)
+            .filter(|model| names.insert(model.name.clone()))
+            .collect();
+        Ok(ListLocalModelsResponse { models })
+    }
+
+    /// The models loaded on every healthy node.
+    async fn list_running(&self) -> Result<ListRunningModelsResponse, OllamaError> {
+        let responses = self
+            .broadcast(|client| async move { client.list_running().await })
+            .await?;
+
+        let models = responses
+            .into_iter()
+            .flat_map(|response| response.models)
+            .collect();
+        Ok(ListRunningModelsResponse { models })
+    }
+
+    async fn show_info(&self, request: ShowModelRequest) -> Result<ShowModelResponse, OllamaError> {
+        let (response, _) = self
+            .route(None, |client| {
+                let request = request.clone();
+                async move { client.show_info(request).await }
+            })
+            .await?;
+        Ok(response)
+    }
+
+    async fn copy(&self, request: CopyModelRequest) -> Result<(), OllamaError> {
+        self.route_once(|client| {
+            let request = request.clone();
+            async move { client.copy(request).await }
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, request: DeleteModelRequest) -> Result<(), OllamaError> {
+        self.route_once(|client| {
+            let request = request.clone();
+            async move { client.delete(request).await }
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn pull(
+        &self,
+        request: PullModelRequest,
+    ) -> Result<OllamaResponse<PullModelResponse>, OllamaError> {
+        let (response, in_flight) = self
+            .route(None, |client| {
+                let request = request.clone();
+                async move { client.pull(request).await }
+            })
+            .await?;
+        Ok(response.hold(in_flight))
+    }
+
+    async fn push(
+        &self,
+        request: PushModelRequest,
+    ) -> Result<OllamaResponse<PushModelResponse>, OllamaError> {
+        let (response, in_flight) = self
+            .route(None, |client| {
+                let request = request.clone();
+                async move { client.push(request).await }
+            })
+            .await?;
+        Ok(response.hold(in_flight))
+    }
+}
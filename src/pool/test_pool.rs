@@ -0,0 +1,190 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+    use tokio_stream::StreamExt;
+
+    use crate::{
+        api::OllamaApi,
+        chat_completion::{
+            message::{Message, Role},
+            request::ChatCompletionRequest,
+        },
+        client::{CircuitBreaker, CircuitState, OllamaClientBuilder},
+        control::RequestControlBuilder,
+        errors::OllamaError,
+        model::delete::DeleteModelRequest,
+        pool::OllamaPool,
+        testing::{MockOllama, MockResponse},
+    };
+
+    fn request(model: &str) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: model.to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: String::from("Hi"),
+                images: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn running(model: &str) -> MockResponse {
+        MockResponse::json(json!({
+            "models": [{
+                "name": model,
+                "model": model,
+                "size": 1,
+                "digest": "a",
+                "expires_at": "2024-06-01T00:00:00Z",
+            }]
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_pool_prefers_loaded_model() {
+        let (a, b) = (MockOllama::start().await, MockOllama::start().await);
+        b.respond("/api/ps", running("llama3:latest"));
+        let pool = OllamaPool::new([a.url(), b.url()]);
+
+        for _ in 0..2 {
+            let response = pool.chat(request("llama3")).await.unwrap();
+            response.as_response().await.unwrap();
+        }
+        assert_eq!(a.requests_to("/api/chat").len(), 0);
+        assert_eq!(b.requests_to("/api/chat").len(), 2);
+
+        // Once served by a node, the model is considered loaded there.
+        pool.chat(request("phi3")).await.unwrap();
+        assert_eq!(a.requests_to("/api/chat").len(), 1);
+        let status = pool.status();
+        assert_eq!(status[0].loaded_models, vec!["phi3:latest"]);
+        assert_eq!(status[1].loaded_models, vec!["llama3:latest"]);
+    }
+
+    #[tokio::test]
+    async fn test_pool_least_loaded() {
+        let (a, b) = (MockOllama::start().await, MockOllama::start().await);
+        a.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["a", "b"]).with_delay(Duration::from_millis(50)),
+        );
+        let pool = OllamaPool::new([a.url(), b.url()]);
+
+        let mut first = pool
+            .chat(request("llama3"))
+            .await
+            .unwrap()
+            .as_stream()
+            .await
+            .unwrap();
+        assert_eq!(pool.status()[0].in_flight, 1);
+
+        pool.chat(request("mistral")).await.unwrap();
+        assert_eq!(b.requests_to("/api/chat").len(), 1);
+
+        while first.next().await.is_some() {}
+        assert_eq!(pool.status()[0].in_flight, 0);
+    }
+
+    #[tokio::test]
+    async fn test_pool_failover() {
+        let (a, b) = (MockOllama::start().await, MockOllama::start().await);
+        a.respond("/api/chat", MockResponse::error(500, "out of memory"));
+        let pool = OllamaPool::new([a.url(), b.url()]);
+
+        let response = pool.chat(request("llama3")).await.unwrap();
+        let response = response.as_response().await.unwrap();
+        assert_eq!(response.message.unwrap().content, "Hello from mock");
+        assert_eq!(a.requests_to("/api/chat").len(), 1);
+        assert_eq!(b.requests_to("/api/chat").len(), 1);
+        assert!(pool.status()[0].healthy);
+
+        let pool = OllamaPool::new([String::from("http://127.0.0.1:1"), b.url()]);
+        pool.chat(request("llama3")).await.unwrap();
+        assert!(!pool.status()[0].healthy);
+        assert!(pool.status()[1].healthy);
+
+        let pool = OllamaPool::new(["http://127.0.0.1:1"]);
+        assert!(pool.chat(request("llama3")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pool_doesnt_fail_over() {
+        let (a, b) = (MockOllama::start().await, MockOllama::start().await);
+        let pool = OllamaPool::new([a.url(), b.url()]);
+
+        // The request itself is wrong.
+        a.respond("/api/chat", MockResponse::error(400, "invalid options"));
+        let err = pool.chat(request("llama3")).await.err().unwrap();
+        assert!(matches!(err, OllamaError::OllamaError(_)));
+
+        // Not idempotent.
+        a.respond("/api/delete", MockResponse::error(500, "out of disk"));
+        let delete = DeleteModelRequest {
+            name: String::from("llama3"),
+        };
+        let err = pool.delete(delete).await.err().unwrap();
+        assert!(matches!(err, OllamaError::ServerError(_)));
+
+        // The caller's own timeout says nothing about the node.
+        a.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["slow"]).with_delay(Duration::from_millis(200)),
+        );
+        let control = RequestControlBuilder::default()
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let result = pool.chat_with_control(request("llama3"), control).await;
+        let err = match result {
+            Ok(response) => response.as_response().await.err().unwrap(),
+            Err(e) => e,
+        };
+        assert!(matches!(err, OllamaError::Timeout(_)));
+        assert!(pool.status()[0].healthy);
+
+        assert!(b.requests_to("/api/chat").is_empty());
+        assert!(b.requests_to("/api/delete").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pool_circuit_breaker_per_node() {
+        let (a, b) = (MockOllama::start().await, MockOllama::start().await);
+        a.respond("/api/chat", MockResponse::error(500, "out of memory"));
+        let client = |url: String| {
+            OllamaClientBuilder::default()
+                .host(url)
+                .circuit_breaker(CircuitBreaker::new().failure_threshold(1))
+                .build()
+                .unwrap()
+        };
+        let pool = OllamaPool::from_clients([client(a.url()), client(b.url())]);
+
+        for _ in 0..2 {
+            let response = pool.chat(request("llama3")).await.unwrap();
+            response.as_response().await.unwrap();
+        }
+        // The failures of one node don't open the circuit of the other.
+        assert_eq!(a.requests_to("/api/chat").len(), 1);
+        assert_eq!(b.requests_to("/api/chat").len(), 2);
+        let status = pool.status();
+        assert_eq!(status[0].circuit, Some(CircuitState::Open));
+        assert_eq!(status[1].circuit, Some(CircuitState::Closed));
+    }
+
+    #[tokio::test]
+    async fn test_pool_list_running() {
+        let (a, b) = (MockOllama::start().await, MockOllama::start().await);
+        let pool = OllamaPool::new([a.url(), b.url()]);
+        pool.check_health().await;
+
+        a.respond("/api/ps", running("llama3:latest"));
+        b.respond("/api/ps", running("phi3:latest"));
+        let running = pool.list_running().await.unwrap();
+        let names: Vec<_> = running.models.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["llama3:latest", "phi3:latest"]);
+    }
+}
@@ -0,0 +1,68 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::OllamaError;
+
+/// The API keys accepted by a [`crate::proxy::Proxy`], usually loaded from a JSON file:
+///
+/// ```json
+/// { "keys": { "secret-1": { "name": "search-team", "models": ["llama3*"], "token_quota": 1000000 } } }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// Policies by API key.
+    pub keys: HashMap<String, KeyPolicy>,
+}
+
+impl ProxyConfig {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, OllamaError> {
+        let content = fs::read_to_string(path).map_err(|e| OllamaError::IoError(e.to_string()))?;
+        serde_json::from_str(&content).map_err(|e| OllamaError::ParseError(e.to_string()))
+    }
+}
+
+/// What one API key is allowed to do.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyPolicy {
+    /// Name reported in usage records, e.g. the team owning the key.
+    pub name: String,
+
+    /// Models the key may use, any model when empty. A trailing `*` matches
+    /// any suffix, and a name without tag also matches its `latest` tag.
+    #[serde(default)]
+    pub models: Vec<String>,
+
+    /// Maximum number of tokens generated for the key, counted from `eval_count`.
+    /// Checked before each request, unlimited when unset. The quota is soft: the
+    /// tokens of a request are only counted once it completes, so requests
+    /// running at the same time may together exceed it.
+    #[serde(default)]
+    pub token_quota: Option<u64>,
+
+    /// Maximum number of requests of the key processed at the same time,
+    /// unlimited when unset.
+    #[serde(default)]
+    pub max_concurrent: Option<usize>,
+
+    /// Allow the model management endpoints: create, copy, delete, pull and push.
+    #[serde(default)]
+    pub admin: bool,
+}
+
+impl KeyPolicy {
+    pub fn allows_model(&self, model: &str) -> bool {
+        self.models.is_empty()
+            || self
+                .models
+                .iter()
+                .any(|pattern| match pattern.strip_suffix('*') {
+                    Some(prefix) => model.starts_with(prefix),
+                    None => {
+                        pattern == model
+                            || format!("{pattern}:latest") == model
+                            || format!("{model}:latest") == *pattern
+                    }
+                })
+    }
+}
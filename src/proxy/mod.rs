@@ -0,0 +1,8 @@
+pub mod keys;
+pub mod server;
+
+// test module
+mod test_proxy;
+
+pub use keys::{KeyPolicy, ProxyConfig};
+pub use server::{Proxy, UsageRecord};
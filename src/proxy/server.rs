@@ -0,0 +1,529 @@
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use async_stream::stream;
+use axum::{
+    body::{Body, Bytes},
+    extract::{rejection::JsonRejection, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_stream::StreamExt;
+
+use super::keys::{KeyPolicy, ProxyConfig};
+use crate::{
+    client::OllamaClient,
+    errors::OllamaError,
+    model::{
+        copy::CopyModelRequest, create::CreateModelRequest, delete::DeleteModelRequest,
+        pull::PullModelRequest, push::PushModelRequest, show_info::ShowModelRequest,
+    },
+    response::{split_lines, OllamaResponse, StreamHandler},
+};
+/// Usage of one request, reported once its response has been fully sent or the
+/// client went away.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageRecord {
+    /// Name of the API key, see [`KeyPolicy::name`].
+    pub key: String,
+
+    /// The requested endpoint, e.g. `/api/chat`.
+    pub endpoint: String,
+
+    pub model: String,
+
+    /// Number of tokens in the prompt, from `prompt_eval_count`.
+    pub prompt_tokens: u64,
+
+    /// Number of generated tokens, from `eval_count`, or the number of chunks sent
+    /// if the response was cut short.
+    pub eval_tokens: u64,
+
+    /// Tokens generated for the key so far, including this request.
+    pub total_eval_tokens: u64,
+
+    /// Time spent processing the request, in milliseconds.
+    pub duration_ms: u64,
+}
+
+type UsageCallback = Arc<dyn Fn(&UsageRecord) + Send + Sync>;
+
+/// A reverse proxy speaking the native Ollama API, in front of one server.
+/// Requests must carry an API key as a bearer token; each key has a model
+/// allowlist, a token quota and a concurrency limit. The bodies of generation and
+/// embeddings requests are forwarded as sent, whatever their fields.
+#[derive(Clone)]
+pub struct Proxy {
+    client: OllamaClient,
+    keys: Arc<HashMap<String, Arc<KeyState>>>,
+    on_usage: Option<UsageCallback>,
+}
+
+struct KeyState {
+    policy: KeyPolicy,
+    eval_tokens: AtomicU64,
+    permits: Option<Arc<Semaphore>>,
+}
+
+impl Proxy {
+    pub fn new(client: OllamaClient, config: ProxyConfig) -> Self {
+        let keys = config
+            .keys
+            .into_iter()
+            .map(|(key, policy)| {
+                let state = KeyState {
+                    permits: policy.max_concurrent.map(|n| Arc::new(Semaphore::new(n))),
+                    eval_tokens: AtomicU64::new(0),
+                    policy,
+                };
+                (key, Arc::new(state))
+            })
+            .collect();
+
+        Self {
+            client,
+            keys: Arc::new(keys),
+            on_usage: None,
+        }
+    }
+
+    /// Call `on_usage` after each chat, generate and embeddings request.
+    pub fn on_usage(mut self, on_usage: impl Fn(&UsageRecord) + Send + Sync + 'static) -> Self {
+        self.on_usage = Some(Arc::new(on_usage));
+        self
+    }
+
+    /// Tokens generated so far for the key named `name`.
+    pub fn eval_tokens(&self, name: &str) -> Option<u64> {
+        self.keys
+            .values()
+            .find(|state| state.policy.name == name)
+            .map(|state| state.eval_tokens.load(Ordering::Relaxed))
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", get(|| async { "Ollama is running" }))
+            .route("/api/chat", post(chat))
+            .route("/api/generate", post(generate))
+            .route("/api/embeddings", post(embeddings))
+            .route("/api/embed", post(embed))
+            .route("/api/version", get(version))
+            .route("/api/tags", get(list_local))
+            .route("/api/ps", get(list_running))
+            .route("/api/show", post(show_info))
+            .route("/api/create", post(create))
+            .route("/api/copy", post(copy))
+            .route("/api/delete", delete(remove))
+            .route("/api/pull", post(pull))
+            .route("/api/push", post(push))
+            .with_state(self.clone())
+    }
+
+    fn authorize(&self, headers: &HeaderMap) -> Result<Arc<KeyState>, ProxyError> {
+        let key = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| ProxyError::new(StatusCode::UNAUTHORIZED, "missing API key"))?;
+        self.keys
+            .get(key.trim())
+            .cloned()
+            .ok_or_else(|| ProxyError::new(StatusCode::UNAUTHORIZED, "invalid API key"))
+    }
+
+    /// Check that `key` may run `model` now, taking one of its concurrency permits.
+    /// The token quota is soft: requests admitted under it may together exceed it.
+    fn admit(&self, key: Arc<KeyState>, endpoint: &str, model: &str) -> Result<Usage, ProxyError> {
+        let policy = &key.policy;
+        if !policy.allows_model(model) {
+            return Err(ProxyError::new(
+                StatusCode::FORBIDDEN,
+                format!("model '{model}' is not allowed for this API key"),
+            ));
+        }
+        if let Some(quota) = policy.token_quota {
+            if key.eval_tokens.load(Ordering::Relaxed) >= quota {
+                return Err(ProxyError::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    format!("token quota of {quota} exceeded"),
+                ));
+            }
+        }
+        let permit = match &key.permits {
+            Some(permits) => Some(permits.clone().try_acquire_owned().map_err(|_| {
+                ProxyError::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "too many concurrent requests for this API key",
+                )
+            })?),
+            None => None,
+        };
+
+        Ok(Usage {
+            on_usage: self.on_usage.clone(),
+            key,
+            endpoint: endpoint.to_string(),
+            model: model.to_string(),
+            started: Instant::now(),
+            _permit: permit,
+        })
+    }
+
+    fn admin(&self, headers: &HeaderMap) -> Result<(), ProxyError> {
+        match self.authorize(headers)?.policy.admin {
+            true => Ok(()),
+            false => Err(ProxyError::new(
+                StatusCode::FORBIDDEN,
+                "this API key cannot manage models",
+            )),
+        }
+    }
+}
+
+/// An admitted request, holding its concurrency permit until the usage is recorded.
+struct Usage {
+    on_usage: Option<UsageCallback>,
+    key: Arc<KeyState>,
+    endpoint: String,
+    model: String,
+    started: Instant,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Usage {
+    fn record(self, prompt_tokens: u64, eval_tokens: u64) {
+        let total_eval_tokens = self
+            .key
+            .eval_tokens
+            .fetch_add(eval_tokens, Ordering::Relaxed)
+            + eval_tokens;
+        if let Some(on_usage) = &self.on_usage {
+            on_usage(&UsageRecord {
+                key: self.key.policy.name.clone(),
+                endpoint: self.endpoint.clone(),
+                model: self.model.clone(),
+                prompt_tokens,
+                eval_tokens,
+                total_eval_tokens,
+                duration_ms: self.started.elapsed().as_millis() as u64,
+            });
+        }
+    }
+}
+
+/// The usage of a streamed generation. If the stream ends or is dropped before
+/// the final chunk, e.g. when the client disconnects, one eval token is recorded
+/// per forwarded chunk.
+struct Metered {
+    usage: Option<Usage>,
+    chunks: u64,
+}
+
+impl Metered {
+    fn record(&mut self, prompt_tokens: u64, eval_tokens: u64) {
+        if let Some(usage) = self.usage.take() {
+            usage.record(prompt_tokens, eval_tokens);
+        }
+    }
+}
+
+impl Drop for Metered {
+    fn drop(&mut self) {
+        self.record(0, self.chunks);
+    }
+}
+
+/// Token counts of a line of a response: those of the final chunk of a
+/// generation, or of a response which isn't streamed.
+fn token_counts(line: &[u8]) -> Option<(u64, u64)> {
+    let line: Value = serde_json::from_slice(line).ok()?;
+    if line
+        .get("done")
+        .is_some_and(|done| done != &Value::Bool(true))
+    {
+        return None;
+    }
+    let count = |name: &str| line.get(name).and_then(Value::as_u64).unwrap_or_default();
+    Some((count("prompt_eval_count"), count("eval_count")))
+}
+
+async fn chat(
+    State(proxy): State<Proxy>,
+    headers: HeaderMap,
+    request: Result<Json<Value>, JsonRejection>,
+) -> Result<Response, ProxyError> {
+    forward(proxy, headers, "/api/chat", request).await
+}
+
+async fn generate(
+    State(proxy): State<Proxy>,
+    headers: HeaderMap,
+    request: Result<Json<Value>, JsonRejection>,
+) -> Result<Response, ProxyError> {
+    forward(proxy, headers, "/api/generate", request).await
+}
+
+async fn embeddings(
+    State(proxy): State<Proxy>,
+    headers: HeaderMap,
+    request: Result<Json<Value>, JsonRejection>,
+) -> Result<Response, ProxyError> {
+    forward(proxy, headers, "/api/embeddings", request).await
+}
+
+async fn embed(
+    State(proxy): State<Proxy>,
+    headers: HeaderMap,
+    request: Result<Json<Value>, JsonRejection>,
+) -> Result<Response, ProxyError> {
+    forward(proxy, headers, "/api/embed", request).await
+}
+
+async fn version(State(proxy): State<Proxy>) -> Result<Response, ProxyError> {
+    Ok(Json(proxy.client.version().await?).into_response())
+}
+
+/// Send the body of a request to `endpoint` to Ollama as is, and its response
+/// back as sent by Ollama, recording its usage once complete.
+async fn forward(
+    proxy: Proxy,
+    headers: HeaderMap,
+    endpoint: &str,
+    request: Result<Json<Value>, JsonRejection>,
+) -> Result<Response, ProxyError> {
+    let key = proxy.authorize(&headers)?;
+    let Json(request) = request?;
+    let model = request
+        .get("model")
+        .and_then(Value::as_str)
+        .ok_or_else(|| ProxyError::new(StatusCode::BAD_REQUEST, "missing field `model`"))?;
+    let usage = proxy.admit(key, endpoint, model)?;
+
+    let response = proxy.client.forward(endpoint, request).await?;
+    let content_type = response.headers().get(header::CONTENT_TYPE).cloned();
+    let mut lines = Box::pin(split_lines(response.bytes_stream()));
+    let body = stream! {
+        let mut metered = Metered { usage: Some(usage), chunks: 0 };
+        while let Some(item) = lines.next().await {
+            match item {
+                Ok(line) => {
+                    match token_counts(&line) {
+                        Some((prompt_tokens, eval_tokens)) => metered.record(prompt_tokens, eval_tokens),
+                        None => metered.chunks += 1,
+                    }
+                    yield Ok::<_, Infallible>(line);
+                }
+                Err(e) => {
+                    let error = OllamaError::StreamError(e.to_string());
+                    yield Ok(json_line(&ProxyError::from(error).body()));
+                    break;
+                }
+            }
+        }
+    };
+
+    let mut response = Response::builder();
+    if let Some(content_type) = content_type {
+        response = response.header(header::CONTENT_TYPE, content_type);
+    }
+    Ok(response.body(Body::from_stream(body)).unwrap())
+}
+
+async fn list_local(
+    State(proxy): State<Proxy>,
+    headers: HeaderMap,
+) -> Result<Response, ProxyError> {
+    let key = proxy.authorize(&headers)?;
+    let mut response = proxy.client.list_local().await?;
+    response
+        .models
+        .retain(|model| key.policy.allows_model(&model.name));
+    Ok(Json(response).into_response())
+}
+
+async fn list_running(
+    State(proxy): State<Proxy>,
+    headers: HeaderMap,
+) -> Result<Response, ProxyError> {
+    let key = proxy.authorize(&headers)?;
+    let mut response = proxy.client.list_running().await?;
+    response
+        .models
+        .retain(|model| key.policy.allows_model(&model.name));
+    Ok(Json(response).into_response())
+}
+
+async fn show_info(
+    State(proxy): State<Proxy>,
+    headers: HeaderMap,
+    request: Result<Json<ShowModelRequest>, JsonRejection>,
+) -> Result<Response, ProxyError> {
+    let key = proxy.authorize(&headers)?;
+    let Json(request) = request?;
+    if !key.policy.allows_model(&request.name) {
+        return Err(ProxyError::new(
+            StatusCode::FORBIDDEN,
+            format!("model '{}' is not allowed for this API key", request.name),
+        ));
+    }
+    Ok(Json(proxy.client.show_info(request).await?).into_response())
+}
+
+async fn create(
+    State(proxy): State<Proxy>,
+    headers: HeaderMap,
+    request: Result<Json<CreateModelRequest>, JsonRejection>,
+) -> Result<Response, ProxyError> {
+    proxy.admin(&headers)?;
+    let Json(request) = request?;
+    forward_progress(proxy.client.create(request).await?).await
+}
+
+async fn copy(
+    State(proxy): State<Proxy>,
+    headers: HeaderMap,
+    request: Result<Json<CopyModelRequest>, JsonRejection>,
+) -> Result<Response, ProxyError> {
+    proxy.admin(&headers)?;
+    let Json(request) = request?;
+    proxy.client.copy(request).await?;
+    Ok(StatusCode::OK.into_response())
+}
+
+async fn remove(
+    State(proxy): State<Proxy>,
+    headers: HeaderMap,
+    request: Result<Json<DeleteModelRequest>, JsonRejection>,
+) -> Result<Response, ProxyError> {
+    proxy.admin(&headers)?;
+    let Json(request) = request?;
+    proxy.client.delete(request).await?;
+    Ok(StatusCode::OK.into_response())
+}
+
+async fn pull(
+    State(proxy): State<Proxy>,
+    headers: HeaderMap,
+    request: Result<Json<PullModelRequest>, JsonRejection>,
+) -> Result<Response, ProxyError> {
+    proxy.admin(&headers)?;
+    let Json(request) = request?;
+    forward_progress(proxy.client.pull(request).await?).await
+}
+
+async fn push(
+    State(proxy): State<Proxy>,
+    headers: HeaderMap,
+    request: Result<Json<PushModelRequest>, JsonRejection>,
+) -> Result<Response, ProxyError> {
+    proxy.admin(&headers)?;
+    let Json(request) = request?;
+    forward_progress(proxy.client.push(request).await?).await
+}
+
+/// Stream the progress of a model management request.
+async fn forward_progress<T>(response: OllamaResponse<T>) -> Result<Response, ProxyError>
+where
+    T: StreamHandler + Serialize + DeserializeOwned + Send + 'static,
+{
+    let mut chunks = response.as_stream().await?;
+    let body = stream! {
+        while let Some(item) = chunks.next().await {
+            match item {
+                Ok(chunk) => yield Ok::<_, Infallible>(json_line(&chunk)),
+                Err(e) => {
+                    yield Ok(json_line(&ProxyError::from(e).body()));
+                    break;
+                }
+            }
+        }
+    };
+    Ok(ndjson(body))
+}
+
+fn json_line(value: &impl Serialize) -> Bytes {
+    let mut line = serde_json::to_vec(value).unwrap_or_default();
+    line.push(b'\n');
+    Bytes::from(line)
+}
+
+fn ndjson<S>(body: S) -> Response
+where
+    S: tokio_stream::Stream<Item = Result<Bytes, Infallible>> + Send + 'static,
+{
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/x-ndjson")
+        .body(Body::from_stream(body))
+        .unwrap()
+}
+
+/// An error in the Ollama format: `{"error": message}`.
+struct ProxyError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ProxyError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn body(&self) -> Value {
+        json!({ "error": self.message })
+    }
+}
+
+impl From<OllamaError> for ProxyError {
+    fn from(value: OllamaError) -> Self {
+        let status = match &value {
+            e if e.is_model_not_found() => StatusCode::NOT_FOUND,
+            OllamaError::InvalidParameter(_) => StatusCode::BAD_REQUEST,
+            OllamaError::RequestError(_) | OllamaError::StreamError(_) => StatusCode::BAD_GATEWAY,
+            OllamaError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            OllamaError::CircuitOpen(_) => StatusCode::SERVICE_UNAVAILABLE,
+            OllamaError::QueueFull(_) => StatusCode::TOO_MANY_REQUESTS,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        // Keep the message of errors returned by Ollama as is.
+        let message = match value {
+            OllamaError::OllamaError(body) | OllamaError::ServerError(body) => {
+                serde_json::from_str::<Value>(&body)
+                    .ok()
+                    .and_then(|body| body.get("error")?.as_str().map(String::from))
+                    .unwrap_or(body)
+            }
+            e => e.to_string(),
+        };
+
+        Self::new(status, message)
+    }
+}
+
+impl From<JsonRejection> for ProxyError {
+    fn from(value: JsonRejection) -> Self {
+        Self::new(value.status(), value.body_text())
+    }
+}
+
+impl IntoResponse for ProxyError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body())).into_response()
+    }
+}
@@ -0,0 +1,295 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use serde_json::json;
+    use tokio_stream::StreamExt;
+
+    use crate::{
+        chat_completion::{
+            message::{Message, Role},
+            request::ChatCompletionRequest,
+        },
+        client::{OllamaClient, OllamaClientBuilder},
+        errors::OllamaError,
+        model::pull::PullModelRequest,
+        proxy::{KeyPolicy, Proxy, ProxyConfig, UsageRecord},
+        testing::{MockOllama, MockResponse},
+    };
+
+    fn config() -> ProxyConfig {
+        let mut config = ProxyConfig::default();
+        config.keys.insert(
+            String::from("team-key"),
+            KeyPolicy {
+                name: String::from("team"),
+                models: vec![String::from("llama3*")],
+                token_quota: Some(5),
+                max_concurrent: Some(1),
+                admin: false,
+            },
+        );
+        config.keys.insert(
+            String::from("admin-key"),
+            KeyPolicy {
+                name: String::from("admin"),
+                admin: true,
+                ..Default::default()
+            },
+        );
+        config
+    }
+
+    async fn start(proxy: &Proxy) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = proxy.router();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    fn client(url: &str, api_key: &str) -> OllamaClient {
+        OllamaClientBuilder::default()
+            .host(url)
+            .api_key(api_key)
+            .build()
+            .unwrap()
+    }
+
+    fn request(model: &str) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: model.to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: String::from("Hi"),
+                images: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn error_message(err: OllamaError) -> String {
+        match err {
+            OllamaError::OllamaError(body) => {
+                let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+                body["error"].as_str().unwrap().to_string()
+            }
+            e => panic!("unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn test_allows_model() {
+        let policy = KeyPolicy {
+            models: vec![String::from("llama3*"), String::from("phi3")],
+            ..Default::default()
+        };
+        assert!(policy.allows_model("llama3:70b"));
+        assert!(policy.allows_model("phi3"));
+        assert!(policy.allows_model("phi3:latest"));
+        assert!(!policy.allows_model("phi3:mini"));
+        assert!(!policy.allows_model("mistral"));
+        assert!(KeyPolicy::default().allows_model("mistral"));
+    }
+
+    #[tokio::test]
+    async fn test_proxy_auth_and_quota() {
+        let mock = MockOllama::start().await;
+        let records: Arc<Mutex<Vec<UsageRecord>>> = Arc::default();
+        let proxy = Proxy::new(mock.client(), config()).on_usage({
+            let records = records.clone();
+            move |record| records.lock().unwrap().push(record.clone())
+        });
+        let url = start(&proxy).await;
+
+        let err = OllamaClient::new(&url).chat(request("llama3")).await;
+        assert_eq!(error_message(err.err().unwrap()), "missing API key");
+        let err = client(&url, "nope").chat(request("llama3")).await;
+        assert_eq!(error_message(err.err().unwrap()), "invalid API key");
+
+        let team = client(&url, "team-key");
+        let err = team.chat(request("mistral")).await.err().unwrap();
+        assert_eq!(
+            error_message(err),
+            "model 'mistral' is not allowed for this API key"
+        );
+
+        // The mock generates 3 tokens per request, the quota is 5.
+        let response = team.chat(request("llama3")).await.unwrap();
+        let response = response.as_response().await.unwrap();
+        assert_eq!(response.message.unwrap().content, "Hello from mock");
+        let mut non_streamed = request("llama3");
+        non_streamed.stream = Some(false);
+        let response = team.chat(non_streamed).await.unwrap();
+        assert_eq!(response.response().await.unwrap().eval_count, Some(3));
+
+        let err = team.chat(request("llama3")).await.err().unwrap();
+        assert_eq!(error_message(err), "token quota of 5 exceeded");
+        assert_eq!(proxy.eval_tokens("team"), Some(6));
+
+        let records = records.lock().unwrap().clone();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].key, "team");
+        assert_eq!(records[1].endpoint, "/api/chat");
+        assert_eq!(records[1].prompt_tokens, 10);
+        assert_eq!(records[1].total_eval_tokens, 6);
+        assert_eq!(mock.requests_to("/api/chat").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_proxy_concurrency_limit() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["a", "b", "c"])
+                .with_delay(Duration::from_millis(100)),
+        );
+        let proxy = Proxy::new(mock.client(), config());
+        let url = start(&proxy).await;
+        let team = client(&url, "team-key");
+
+        let mut first = team
+            .chat(request("llama3"))
+            .await
+            .unwrap()
+            .as_stream()
+            .await
+            .unwrap();
+        let err = team.chat(request("llama3")).await.err().unwrap();
+        assert_eq!(
+            error_message(err),
+            "too many concurrent requests for this API key"
+        );
+
+        while first.next().await.is_some() {}
+        drop(first);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(team.chat(request("llama3")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_proxy_records_usage_on_disconnect() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["a", "b", "c", "d", "e", "f"])
+                .with_delay(Duration::from_millis(100)),
+        );
+        let records: Arc<Mutex<Vec<UsageRecord>>> = Arc::default();
+        let proxy = Proxy::new(mock.client(), config()).on_usage({
+            let records = records.clone();
+            move |record| records.lock().unwrap().push(record.clone())
+        });
+        let url = start(&proxy).await;
+        let team = client(&url, "team-key");
+
+        let mut stream = team
+            .chat(request("llama3"))
+            .await
+            .unwrap()
+            .as_stream()
+            .await
+            .unwrap();
+        assert!(stream.next().await.unwrap().is_ok());
+        drop(stream);
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        // Charged for the chunks forwarded before the client went away.
+        let records = records.lock().unwrap().clone();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].prompt_tokens, 0);
+        assert!((1..6).contains(&records[0].eval_tokens));
+        assert_eq!(proxy.eval_tokens("team"), Some(records[0].eval_tokens));
+    }
+
+    #[tokio::test]
+    async fn test_proxy_forwards_bodies_as_sent() {
+        let mock = MockOllama::start().await;
+        let proxy = Proxy::new(mock.client(), config());
+        let url = start(&proxy).await;
+        let http = reqwest::Client::new();
+
+        // Fields the crate's request types don't know about, or type differently.
+        let body = json!({
+            "model": "llama3",
+            "messages": [
+                { "role": "user", "content": "What's the weather?" },
+                { "role": "tool", "content": "Sunny" },
+            ],
+            "tools": [{ "type": "function", "function": { "name": "weather" } }],
+            "options": { "stop": ["a", "b"], "num_gpu": 1, "min_p": 0.05 },
+            "keep_alive": "5m",
+            "stream": false,
+        });
+        let response = http
+            .post(format!("{url}/api/chat"))
+            .bearer_auth("team-key")
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        let response: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(response["message"]["content"], "Hello from mock");
+        assert_eq!(mock.requests_to("/api/chat")[0].body, Some(body));
+
+        let body = json!({ "model": "llama3", "input": ["a"], "truncate": false });
+        let response = http
+            .post(format!("{url}/api/embed"))
+            .bearer_auth("team-key")
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        let response: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(response["embeddings"][0][0], 0.1);
+        assert_eq!(mock.requests_to("/api/embed")[0].body, Some(body));
+        assert_eq!(proxy.eval_tokens("team"), Some(3));
+
+        let version = OllamaClient::new(&url).version().await.unwrap();
+        assert_eq!(version.version, "0.0.0-mock");
+    }
+
+    #[tokio::test]
+    async fn test_proxy_management() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/tags",
+            MockResponse::json(json!({
+                "models": [
+                    { "name": "llama3:latest", "modified_at": "", "size": 1, "digest": "a", "details": {} },
+                    { "name": "phi3:latest", "modified_at": "", "size": 1, "digest": "b", "details": {} },
+                ]
+            })),
+        );
+        let proxy = Proxy::new(mock.client(), config());
+        let url = start(&proxy).await;
+
+        let models = client(&url, "team-key").list_local().await.unwrap();
+        let names: Vec<_> = models.models.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["llama3:latest"]);
+
+        let pull = PullModelRequest {
+            name: String::from("llama3"),
+            insecure: None,
+            stream: None,
+        };
+        let err = client(&url, "team-key").pull(pull.clone()).await.err();
+        assert_eq!(
+            error_message(err.unwrap()),
+            "this API key cannot manage models"
+        );
+
+        let progress = client(&url, "admin-key")
+            .pull(pull)
+            .await
+            .unwrap()
+            .as_response()
+            .await
+            .unwrap();
+        assert_eq!(progress.status, "success");
+    }
+}
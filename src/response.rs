@@ -1,16 +1,26 @@
 use std::marker::PhantomData;
 
+use async_stream::stream;
 use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
 use reqwest::StatusCode;
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio_stream::StreamExt;
 
 use crate::{
+    control::Watchdog,
     errors::OllamaError,
     stream_handler::{OllamaStream, StreamHandler},
 };
 
+/// The raw body of a response.
+pub(crate) type BodyStream = std::pin::Pin<
+    Box<dyn tokio_stream::Stream<Item = Result<Bytes, reqwest::Error>> + Send + Sync>,
+>;
+
 pub struct OllamaResponse<T> {
     response: reqwest::Response,
+    watchdog: Option<Watchdog>,
     _marker: PhantomData<T>,
 }
 
@@ -24,7 +34,12 @@ where
     }
 
     pub async fn response(self) -> Result<T, OllamaError> {
-        match self.response.text().await {
+        let text = match &self.watchdog {
+            Some(watchdog) => watchdog.run(self.response.text()).await?,
+            None => self.response.text().await,
+        };
+
+        match text {
             Ok(inner) => match serde_json::from_str(&inner) {
                 Ok(content) => Ok(content),
                 Err(e) => Err(OllamaError::ParseError(e.to_string())),
@@ -36,26 +51,59 @@ where
 
 impl<T> OllamaResponse<T>
 where
-    T: StreamHandler + DeserializeOwned,
+    T: StreamHandler + DeserializeOwned + Send + 'static,
 {
     pub async fn stream(self) -> Result<OllamaStream<T>, OllamaError> {
-        let adapted_stream = T::adapt_stream(self.response.bytes_stream()).await;
-        Ok(adapted_stream)
+        self.as_stream().await
     }
 
     pub async fn as_stream(self) -> Result<OllamaStream<T>, OllamaError> {
         let raw_stream = self.response.bytes_stream();
         let adapted_stream = T::adapt_stream(raw_stream).await;
-        Ok(adapted_stream)
+        match self.watchdog {
+            Some(watchdog) => Ok(guard_stream(adapted_stream, watchdog)),
+            None => Ok(adapted_stream),
+        }
     }
 
     pub async fn as_response(self) -> Result<T, OllamaError> {
         let raw_stream = self.response.bytes_stream();
-        let response = T::stream_to_response(raw_stream).await?;
+        let response = match self.watchdog {
+            Some(watchdog) => {
+                let raw_stream = watchdog.track(raw_stream);
+                watchdog.run(T::stream_to_response(raw_stream)).await??
+            }
+            None => T::stream_to_response(raw_stream).await?,
+        };
         Ok(response)
     }
 }
 
+/// Stop reading `input` once the request is cancelled or timed out. The underlying
+/// response is dropped with the stream, which closes the connection.
+fn guard_stream<T: Send + 'static>(
+    mut input: OllamaStream<T>,
+    watchdog: Watchdog,
+) -> OllamaStream<T> {
+    let guarded = stream! {
+        loop {
+            match watchdog.run(input.next()).await {
+                Ok(Some(item)) => {
+                    watchdog.touch();
+                    yield item;
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    yield Err(e);
+                    break;
+                }
+            }
+        }
+    };
+
+    Box::pin(guarded)
+}
+
 #[async_trait]
 pub trait ResponseValidator {
     async fn valid(&self) -> Result<reqwest::Response, OllamaError>;
@@ -71,17 +119,128 @@ pub async fn check_response_valid(
 }
 
 async fn check_status_ok(response: reqwest::Response) -> Result<reqwest::Response, OllamaError> {
-    if response.status() != StatusCode::OK {
+    let status = response.status();
+    if status != StatusCode::OK {
         let err_msg = response.text().await.unwrap_or_default().to_string();
-        return Err(OllamaError::OllamaError(err_msg));
+        return Err(OllamaError::from_status(status, err_msg));
     }
     Ok(response)
 }
 
+/// Re-frame `input` on new lines, so that every chunk holds exactly one whole line
+/// of an NDJSON body, new line included, whatever the chunks received. The end of
+/// a body not ending with a new line is yielded last.
+pub(crate) fn split_lines<S, E>(
+    mut input: S,
+) -> impl tokio_stream::Stream<Item = Result<Bytes, E>> + Send + Sync
+where
+    S: tokio_stream::Stream<Item = Result<Bytes, E>> + Unpin + Send + Sync + 'static,
+    E: Send + Sync + 'static,
+{
+    stream! {
+        let mut pending = BytesMut::new();
+        // Bytes of `pending` already known not to hold a new line.
+        let mut searched = 0;
+        while let Some(item) = input.next().await {
+            match item {
+                Ok(chunk) => {
+                    pending.extend_from_slice(&chunk);
+                    while let Some(end) = pending[searched..].iter().position(|&b| b == b'\n') {
+                        yield Ok(pending.split_to(searched + end + 1).freeze());
+                        searched = 0;
+                    }
+                    searched = pending.len();
+                }
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            }
+        }
+        if !pending.is_empty() {
+            yield Ok(pending.freeze());
+        }
+    }
+}
+
+/// Build a response whose body yields `chunks`, used to replay or share a response.
+pub(crate) fn response_from_stream<S, E>(status: StatusCode, chunks: S) -> reqwest::Response
+where
+    S: tokio_stream::Stream<Item = Result<Bytes, E>> + Send + Sync + 'static,
+    E: Into<Box<dyn std::error::Error + Send + Sync>> + 'static,
+{
+    let body = reqwest::Body::wrap_stream(chunks);
+    http::Response::builder()
+        .status(status)
+        .body(body)
+        .unwrap()
+        .into()
+}
+
+impl<T> OllamaResponse<T>
+where
+    T: Serialize,
+{
+    /// Build a response streaming `items`, one JSON object per chunk. Useful
+    /// for fakes and wrappers implementing [`crate::api::OllamaApi`].
+    pub fn from_items(items: Vec<T>) -> Result<Self, OllamaError> {
+        let mut chunks = vec![];
+        for item in &items {
+            let mut line =
+                serde_json::to_vec(item).map_err(|e| OllamaError::ParseError(e.to_string()))?;
+            line.push(b'\n');
+            chunks.push(Bytes::from(line));
+        }
+        Ok(Self::from_chunks(chunks))
+    }
+}
+
+impl<T> OllamaResponse<T> {
+    /// Build a response whose body is made of the raw `chunks`.
+    pub fn from_chunks(chunks: Vec<Bytes>) -> Self {
+        let chunks = chunks.into_iter().map(Ok::<_, std::convert::Infallible>);
+        response_from_stream(StatusCode::OK, tokio_stream::iter(chunks)).into()
+    }
+
+    pub(crate) fn with_watchdog(mut self, watchdog: Watchdog) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// Keep `guard` alive until the body has been read to the end or the response is dropped.
+    pub(crate) fn hold<G: Send + Sync + 'static>(self, guard: G) -> Self {
+        self.map_body(|mut body| {
+            stream! {
+                let _guard = guard;
+                while let Some(item) = body.next().await {
+                    yield item;
+                }
+            }
+        })
+    }
+
+    /// Replace the body with `f(body)`, keeping the status and timeouts of the response.
+    pub(crate) fn map_body<F, S, E>(self, f: F) -> Self
+    where
+        F: FnOnce(BodyStream) -> S,
+        S: tokio_stream::Stream<Item = Result<Bytes, E>> + Send + Sync + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + 'static,
+    {
+        let status = self.response.status();
+        let body = f(Box::pin(self.response.bytes_stream()));
+        OllamaResponse {
+            response: response_from_stream(status, body),
+            watchdog: self.watchdog,
+            _marker: PhantomData,
+        }
+    }
+}
+
 impl<T> From<reqwest::Response> for OllamaResponse<T> {
     fn from(value: reqwest::Response) -> Self {
         OllamaResponse {
             response: value,
+            watchdog: None,
             _marker: PhantomData::default(),
         }
     }
@@ -0,0 +1,6 @@
+pub mod scheduler;
+
+// test module
+mod test_scheduler;
+
+pub use scheduler::{BatchJob, BatchRequest, BatchResponse, BatchScheduler, DEFAULT_URGENCY};
@@ -0,0 +1,270 @@
+use std::time::Duration;
+
+use futures_util::future::join_all;
+use tokio::time::Instant;
+
+use crate::{
+    api::OllamaApi,
+    chat_completion::{request::ChatCompletionRequest, response::ChatResponse},
+    completion::{request::CompletionRequest, response::CompletionResponse},
+    control::RequestControl,
+    errors::OllamaError,
+    model::canonical_name,
+};
+
+/// Default time left before its deadline from which a request is run ahead of
+/// the requests for the loaded model.
+pub const DEFAULT_URGENCY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+pub enum BatchRequest {
+    Chat(ChatCompletionRequest),
+    Completion(CompletionRequest),
+}
+
+impl BatchRequest {
+    pub fn model(&self) -> &str {
+        match self {
+            Self::Chat(request) => &request.model,
+            Self::Completion(request) => &request.model,
+        }
+    }
+}
+
+impl From<ChatCompletionRequest> for BatchRequest {
+    fn from(value: ChatCompletionRequest) -> Self {
+        Self::Chat(value)
+    }
+}
+
+impl From<CompletionRequest> for BatchRequest {
+    fn from(value: CompletionRequest) -> Self {
+        Self::Completion(value)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum BatchResponse {
+    Chat(ChatResponse),
+    Completion(CompletionResponse),
+}
+
+impl BatchResponse {
+    /// The generated text: the message of a chat, or the response of a completion.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Self::Chat(response) => response.message.as_ref().map(|m| m.content.as_str()),
+            Self::Completion(response) => Some(&response.response),
+        }
+    }
+}
+
+/// A request of a batch, with its scheduling constraints.
+#[derive(Debug, Clone)]
+pub struct BatchJob {
+    pub request: BatchRequest,
+
+    /// Requests with a higher priority are run first, whatever their model.
+    /// (Default: 0)
+    pub priority: i32,
+
+    /// Maximum duration from the start of the batch until the request completes.
+    /// Requests whose deadline has passed before they are sent fail with
+    /// [`OllamaError::Timeout`].
+    pub deadline: Option<Duration>,
+}
+
+impl BatchJob {
+    pub fn new(request: impl Into<BatchRequest>) -> Self {
+        Self {
+            request: request.into(),
+            priority: 0,
+            deadline: None,
+        }
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+}
+
+impl From<ChatCompletionRequest> for BatchJob {
+    fn from(value: ChatCompletionRequest) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<CompletionRequest> for BatchJob {
+    fn from(value: CompletionRequest) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Runs batches of chat and completion requests on one server, ordered to
+/// minimize model swaps.
+///
+/// Requests are run by decreasing priority. Among the requests of the highest
+/// priority left, those for the model last used are run first, unless a request
+/// for another model is due within the urgency window; requests closer to their
+/// deadline, then earlier submitted, go first. The model loaded when the batch
+/// starts, as listed by `/api/ps`, is used first.
+#[derive(Debug, Clone)]
+pub struct BatchScheduler<A> {
+    api: A,
+    parallelism: usize,
+    urgency: Duration,
+}
+
+impl<A: OllamaApi> BatchScheduler<A> {
+    pub fn new(api: A) -> Self {
+        Self {
+            api,
+            parallelism: 1,
+            urgency: DEFAULT_URGENCY,
+        }
+    }
+
+    /// Run up to `parallelism` requests for the same model at a time.
+    /// (Default: 1)
+    pub fn parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Run a request ahead of the requests for the loaded model once its deadline
+    /// is less than `urgency` away.
+    pub fn urgency(mut self, urgency: Duration) -> Self {
+        self.urgency = urgency;
+        self
+    }
+
+    /// The order in which `jobs` would be run with `loaded_model` loaded,
+    /// assuming no deadline passes meanwhile, as indexes into `jobs`.
+    pub fn plan(&self, jobs: &[BatchJob], loaded_model: Option<&str>) -> Vec<usize> {
+        let mut pending: Vec<usize> = (0..jobs.len()).collect();
+        let mut current = loaded_model.map(canonical_name);
+        let mut order = vec![];
+
+        while !pending.is_empty() {
+            let wave = self.next_wave(jobs, &pending, current.as_deref(), Duration::ZERO);
+            pending.retain(|index| !wave.contains(index));
+            current = Some(canonical_name(jobs[wave[0]].request.model()));
+            order.extend(wave);
+        }
+        order
+    }
+
+    /// Run `jobs` and return their results in submission order.
+    pub async fn run(
+        &self,
+        jobs: Vec<impl Into<BatchJob>>,
+    ) -> Vec<Result<BatchResponse, OllamaError>> {
+        let started = Instant::now();
+        let jobs: Vec<BatchJob> = jobs.into_iter().map(Into::into).collect();
+        let mut results: Vec<_> = jobs.iter().map(|_| None).collect();
+        let mut pending: Vec<usize> = (0..jobs.len()).collect();
+        let mut current = self.loaded_model().await;
+
+        while !pending.is_empty() {
+            let elapsed = started.elapsed();
+            pending.retain(|&index| match jobs[index].deadline {
+                Some(deadline) if deadline <= elapsed => {
+                    results[index] = Some(Err(OllamaError::Timeout(format!(
+                        "deadline of {deadline:?} passed before the request was sent"
+                    ))));
+                    false
+                }
+                _ => true,
+            });
+            if pending.is_empty() {
+                break;
+            }
+
+            let wave = self.next_wave(&jobs, &pending, current.as_deref(), elapsed);
+            pending.retain(|index| !wave.contains(index));
+            current = Some(canonical_name(jobs[wave[0]].request.model()));
+
+            let outputs = join_all(wave.iter().map(|&index| {
+                let timeout = jobs[index].deadline.map(|deadline| deadline - elapsed);
+                self.execute(jobs[index].request.clone(), timeout)
+            }))
+            .await;
+            for (index, output) in wave.into_iter().zip(outputs) {
+                results[index] = Some(output);
+            }
+        }
+
+        results.into_iter().map(Option::unwrap).collect()
+    }
+
+    /// The next requests to run, all for the same model.
+    fn next_wave(
+        &self,
+        jobs: &[BatchJob],
+        pending: &[usize],
+        current: Option<&str>,
+        elapsed: Duration,
+    ) -> Vec<usize> {
+        let top = pending.iter().map(|&i| jobs[i].priority).max();
+        let mut candidates: Vec<usize> = pending
+            .iter()
+            .copied()
+            .filter(|&i| Some(jobs[i].priority) == top)
+            .collect();
+        // Closest to their deadline first, then in submission order.
+        candidates.sort_by_key(|&i| (jobs[i].deadline.is_none(), jobs[i].deadline, i));
+
+        let model_of = |index: usize| canonical_name(jobs[index].request.model());
+        let urgent = candidates.iter().copied().find(|&i| {
+            jobs[i]
+                .deadline
+                .is_some_and(|deadline| deadline.saturating_sub(elapsed) <= self.urgency)
+        });
+        let model = match (urgent, current) {
+            (Some(index), _) => model_of(index),
+            (None, Some(current)) if candidates.iter().any(|&i| model_of(i) == current) => {
+                current.to_string()
+            }
+            _ => model_of(candidates[0]),
+        };
+
+        candidates
+            .into_iter()
+            .filter(|&i| model_of(i) == model)
+            .take(self.parallelism)
+            .collect()
+    }
+
+    async fn loaded_model(&self) -> Option<String> {
+        let running = self.api.list_running().await.ok()?;
+        let model = running.models.into_iter().next()?;
+        Some(canonical_name(&model.name))
+    }
+
+    async fn execute(
+        &self,
+        request: BatchRequest,
+        timeout: Option<Duration>,
+    ) -> Result<BatchResponse, OllamaError> {
+        let control = RequestControl {
+            timeout,
+            ..Default::default()
+        };
+        match request {
+            BatchRequest::Chat(request) => {
+                let response = self.api.chat_with_control(request, control).await?;
+                Ok(BatchResponse::Chat(response.as_response().await?))
+            }
+            BatchRequest::Completion(request) => {
+                let response = self.api.completion_with_control(request, control).await?;
+                Ok(BatchResponse::Completion(response.as_response().await?))
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::{json, Value};
+
+    use crate::{
+        chat_completion::{
+            message::{Message, Role},
+            request::ChatCompletionRequest,
+        },
+        completion::request::CompletionRequest,
+        errors::OllamaError,
+        scheduler::{BatchJob, BatchScheduler},
+        testing::{MockOllama, MockResponse},
+    };
+
+    fn chat(model: &str) -> BatchJob {
+        BatchJob::new(ChatCompletionRequest {
+            model: model.to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: String::from("Hi"),
+                images: None,
+            }],
+            ..Default::default()
+        })
+    }
+
+    fn completion(model: &str) -> BatchJob {
+        BatchJob::new(CompletionRequest {
+            model: model.to_string(),
+            prompt: String::from("Hi"),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_plan_groups_by_model() {
+        let mock = MockOllama::start().await;
+        let scheduler = BatchScheduler::new(mock.client());
+        let jobs = vec![
+            chat("a"),
+            chat("b"),
+            completion("a"),
+            chat("b:latest"),
+            chat("c"),
+        ];
+        assert_eq!(scheduler.plan(&jobs, None), vec![0, 2, 1, 3, 4]);
+        assert_eq!(scheduler.plan(&jobs, Some("b")), vec![1, 3, 0, 2, 4]);
+        assert_eq!(scheduler.plan(&jobs, Some("c")), vec![4, 0, 2, 1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_plan_priority_and_deadline() {
+        let mock = MockOllama::start().await;
+        let scheduler = BatchScheduler::new(mock.client()).urgency(Duration::from_secs(10));
+        let jobs = vec![
+            chat("a"),
+            chat("b"),
+            chat("a").with_priority(1),
+            chat("c").with_priority(1),
+            chat("b").with_deadline(Duration::from_secs(5)),
+            chat("a"),
+        ];
+        // Higher priority first, then the urgent request for b, then the rest of b.
+        assert_eq!(scheduler.plan(&jobs, Some("c")), vec![3, 2, 4, 1, 0, 5]);
+
+        let scheduler = scheduler.parallelism(2);
+        assert_eq!(scheduler.plan(&jobs, Some("a")), vec![2, 3, 4, 1, 0, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_run_in_submission_order() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/ps",
+            MockResponse::json(json!({
+                "models": [{
+                    "name": "b:latest",
+                    "model": "b:latest",
+                    "size": 1,
+                    "digest": "b",
+                    "expires_at": "2024-06-01T00:00:00Z",
+                }]
+            })),
+        );
+        mock.respond(
+            "/api/generate",
+            MockResponse::completion_stream("a", &["done"], vec![]),
+        );
+        let scheduler = BatchScheduler::new(mock.client()).parallelism(2);
+
+        let jobs = vec![
+            chat("a"),
+            chat("b"),
+            completion("a"),
+            chat("b"),
+            chat("a").with_deadline(Duration::ZERO),
+        ];
+        let results = scheduler.run(jobs).await;
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].as_ref().unwrap().text(), Some("Hello from mock"));
+        assert_eq!(results[2].as_ref().unwrap().text(), Some("done"));
+        assert!(matches!(results[4], Err(OllamaError::Timeout(_))));
+
+        let models: Vec<Value> = mock
+            .requests()
+            .into_iter()
+            .filter(|request| request.path != "/api/ps")
+            .map(|request| request.body.unwrap()["model"].clone())
+            .collect();
+        assert_eq!(models, vec!["b", "b", "a", "a"]);
+    }
+}
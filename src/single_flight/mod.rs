@@ -0,0 +1,6 @@
+pub mod single_flight;
+
+// test module
+mod test_single_flight;
+
+pub use single_flight::SingleFlight;
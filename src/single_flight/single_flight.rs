@@ -0,0 +1,287 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex, Weak},
+};
+
+use async_stream::stream;
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use tokio::sync::OnceCell;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::{
+    api::{request_key, OllamaApi},
+    chat_completion::{request::ChatCompletionRequest, response::ChatResponse},
+    completion::{request::CompletionRequest, response::CompletionResponse},
+    control::RequestControl,
+    errors::OllamaError,
+    model::{
+        copy::CopyModelRequest,
+        create::{CreateModelRequest, CreateModelResponse},
+        delete::DeleteModelRequest,
+        generate_embeddings::{EmbeddingsRequest, EmbeddingsResponse},
+        list_local::ListLocalModelsResponse,
+        list_running::ListRunningModelsResponse,
+        pull::{PullModelRequest, PullModelResponse},
+        push::{PushModelRequest, PushModelResponse},
+        show_info::{ShowModelRequest, ShowModelResponse},
+    },
+    response::{response_from_stream, BodyStream, OllamaResponse},
+};
+
+type Flights<T> = Arc<Mutex<HashMap<String, Weak<T>>>>;
+
+/// One upstream call shared by every identical request made while it is in flight.
+#[derive(Default)]
+struct Flight {
+    started: OnceCell<Result<(), OllamaError>>,
+    upstream: tokio::sync::Mutex<Option<BodyStream>>,
+    received: Mutex<Received>,
+}
+
+#[derive(Default)]
+struct Received {
+    chunks: Vec<Bytes>,
+    end: Option<Result<(), String>>,
+}
+
+impl Flight {
+    /// True once the upstream call failed or its response has been read to the end.
+    fn is_over(&self) -> bool {
+        matches!(self.started.get(), Some(Err(_))) || self.received.lock().unwrap().end.is_some()
+    }
+
+    /// Send the upstream request with `call`, unless another waiter already did.
+    async fn start<F, Fut>(&self, call: F) -> Result<(), OllamaError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<reqwest::Response, OllamaError>>,
+    {
+        let started = self.started.get_or_init(|| async {
+            let response = call().await?;
+            *self.upstream.lock().await = Some(Box::pin(response.bytes_stream()));
+            Ok(())
+        });
+        started.await.clone()
+    }
+
+    /// The body of the upstream response from its start. The chunks received so
+    /// far are replayed, then whichever subscriber needs the next chunk first
+    /// reads it from upstream for everyone.
+    fn subscribe(
+        self: Arc<Self>,
+    ) -> impl Stream<Item = Result<Bytes, OllamaError>> + Send + Sync + 'static {
+        let flight = self;
+        stream! {
+            let mut position = 0;
+            loop {
+                let next = {
+                    let received = flight.received.lock().unwrap();
+                    match (received.chunks.get(position), &received.end) {
+                        (Some(chunk), _) => Some(Ok(chunk.clone())),
+                        (None, Some(Ok(()))) => break,
+                        (None, Some(Err(e))) => Some(Err(OllamaError::StreamError(e.clone()))),
+                        (None, None) => None,
+                    }
+                };
+                match next {
+                    Some(Ok(chunk)) => {
+                        position += 1;
+                        yield Ok(chunk);
+                        continue;
+                    }
+                    Some(Err(e)) => {
+                        yield Err(e);
+                        break;
+                    }
+                    None => {}
+                }
+
+                let mut upstream = flight.upstream.lock().await;
+                if flight.received.lock().unwrap().chunks.len() > position {
+                    continue;
+                }
+                let item = match upstream.as_mut() {
+                    Some(upstream) => upstream.next().await,
+                    None => None,
+                };
+                let mut received = flight.received.lock().unwrap();
+                match item {
+                    Some(Ok(chunk)) => received.chunks.push(chunk),
+                    Some(Err(e)) => received.end = Some(Err(e.to_string())),
+                    None => received.end = Some(Ok(())),
+                }
+            }
+        }
+    }
+}
+
+/// Shares one upstream call among identical chat, completion and embeddings
+/// requests made while it is in flight. Requests are identical when their
+/// serialized bodies are, options and seed included.
+///
+/// Every waiter gets the whole response, streamed responses included: chunks
+/// received before a waiter joined are replayed to it. Cancelling one waiter, or
+/// its timeouts elapsing, doesn't affect the others. Other calls are forwarded
+/// as is.
+#[derive(Debug, Clone)]
+pub struct SingleFlight<A> {
+    api: A,
+    flights: Flights<Flight>,
+    embeddings: Flights<OnceCell<Result<EmbeddingsResponse, OllamaError>>>,
+}
+
+impl<A: OllamaApi> SingleFlight<A> {
+    pub fn new(api: A) -> Self {
+        Self {
+            api,
+            flights: Arc::default(),
+            embeddings: Arc::default(),
+        }
+    }
+
+    /// The number of distinct upstream calls in flight.
+    pub fn in_flight(&self) -> usize {
+        let flights = self.flights.lock().unwrap();
+        let streaming = flights
+            .values()
+            .filter_map(Weak::upgrade)
+            .filter(|flight| !flight.is_over())
+            .count();
+        let embeddings = self.embeddings.lock().unwrap();
+        let embeddings = embeddings
+            .values()
+            .filter_map(Weak::upgrade)
+            .filter(|cell| !cell.initialized())
+            .count();
+        streaming + embeddings
+    }
+
+    fn flight(&self, key: String) -> Arc<Flight> {
+        let mut flights = self.flights.lock().unwrap();
+        flights.retain(|_, flight| flight.strong_count() > 0);
+        match flights.get(&key).and_then(Weak::upgrade) {
+            Some(flight) if !flight.is_over() => flight,
+            _ => {
+                let flight = Arc::new(Flight::default());
+                flights.insert(key, Arc::downgrade(&flight));
+                flight
+            }
+        }
+    }
+
+    async fn shared<T, F, Fut>(
+        &self,
+        key: String,
+        control: RequestControl,
+        call: F,
+    ) -> Result<OllamaResponse<T>, OllamaError>
+    where
+        T: DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<OllamaResponse<T>, OllamaError>>,
+    {
+        let flight = self.flight(key);
+        let watchdog = control.start();
+        let start = flight.start(|| async { Ok(call().await?.raw_response()) });
+        watchdog.run(start).await??;
+
+        let body = response_from_stream(StatusCode::OK, flight.subscribe());
+        Ok(OllamaResponse::from(body).with_watchdog(watchdog))
+    }
+}
+
+#[async_trait]
+impl<A: OllamaApi> OllamaApi for SingleFlight<A> {
+    async fn chat_with_control(
+        &self,
+        request: ChatCompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<ChatResponse>, OllamaError> {
+        let key = request_key("/api/chat", &request)?;
+        self.shared(key, control, || {
+            self.api
+                .chat_with_control(request, RequestControl::default())
+        })
+        .await
+    }
+
+    async fn completion_with_control(
+        &self,
+        request: CompletionRequest,
+        control: RequestControl,
+    ) -> Result<OllamaResponse<CompletionResponse>, OllamaError> {
+        let key = request_key("/api/generate", &request)?;
+        self.shared(key, control, || {
+            self.api
+                .completion_with_control(request, RequestControl::default())
+        })
+        .await
+    }
+
+    async fn generate_embeddings(
+        &self,
+        request: EmbeddingsRequest,
+    ) -> Result<EmbeddingsResponse, OllamaError> {
+        let key = request_key("/api/embeddings", &request)?;
+        let cell = {
+            let mut embeddings = self.embeddings.lock().unwrap();
+            embeddings.retain(|_, cell| cell.strong_count() > 0);
+            match embeddings.get(&key).and_then(Weak::upgrade) {
+                Some(cell) if !cell.initialized() => cell,
+                _ => {
+                    let cell = Arc::new(OnceCell::new());
+                    embeddings.insert(key, Arc::downgrade(&cell));
+                    cell
+                }
+            }
+        };
+
+        let response = cell.get_or_init(|| self.api.generate_embeddings(request));
+        response.await.clone()
+    }
+
+    async fn create(
+        &self,
+        request: CreateModelRequest,
+    ) -> Result<OllamaResponse<CreateModelResponse>, OllamaError> {
+        self.api.create(request).await
+    }
+
+    async fn list_local(&self) -> Result<ListLocalModelsResponse, OllamaError> {
+        self.api.list_local().await
+    }
+
+    async fn list_running(&self) -> Result<ListRunningModelsResponse, OllamaError> {
+        self.api.list_running().await
+    }
+
+    async fn show_info(&self, request: ShowModelRequest) -> Result<ShowModelResponse, OllamaError> {
+        self.api.show_info(request).await
+    }
+
+    async fn copy(&self, request: CopyModelRequest) -> Result<(), OllamaError> {
+        self.api.copy(request).await
+    }
+
+    async fn delete(&self, request: DeleteModelRequest) -> Result<(), OllamaError> {
+        self.api.delete(request).await
+    }
+
+    async fn pull(
+        &self,
+        request: PullModelRequest,
+    ) -> Result<OllamaResponse<PullModelResponse>, OllamaError> {
+        self.api.pull(request).await
+    }
+
+    async fn push(
+        &self,
+        request: PushModelRequest,
+    ) -> Result<OllamaResponse<PushModelResponse>, OllamaError> {
+        self.api.push(request).await
+    }
+}
@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio_stream::StreamExt;
+
+    use crate::{
+        api::OllamaApi,
+        chat_completion::{
+            message::{Message, Role},
+            request::ChatCompletionRequest,
+        },
+        control::{CancellationToken, RequestControlBuilder},
+        errors::OllamaError,
+        model::generate_embeddings::EmbeddingsRequest,
+        single_flight::SingleFlight,
+        testing::{chat_content, MockOllama, MockResponse},
+    };
+
+    fn request(model: &str) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: model.to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: String::from("Hi"),
+                images: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_chat() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["a", "b", "c"])
+                .with_delay(Duration::from_millis(20)),
+        );
+        let api = SingleFlight::new(mock.client());
+
+        let (first, second) = tokio::join!(
+            chat_content(&api, request("llama3")),
+            chat_content(&api, request("llama3")),
+        );
+        assert_eq!(first, "abc");
+        assert_eq!(second, "abc");
+        assert_eq!(mock.requests_to("/api/chat").len(), 1);
+        assert_eq!(api.in_flight(), 0);
+
+        // Once completed, an identical request is sent again.
+        assert_eq!(
+            chat_content(&api, request("llama3")).await,
+            "Hello from mock"
+        );
+        assert_eq!(chat_content(&api, request("phi3")).await, "Hello from mock");
+        assert_eq!(mock.requests_to("/api/chat").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_late_joiner() {
+        let mock = MockOllama::start().await;
+        mock.respond(
+            "/api/chat",
+            MockResponse::chat_stream("llama3", &["a", "b", "c"])
+                .with_delay(Duration::from_millis(20)),
+        );
+        let api = SingleFlight::new(mock.client());
+
+        let mut first = api
+            .chat(request("llama3"))
+            .await
+            .unwrap()
+            .as_stream()
+            .await
+            .unwrap();
+        assert_eq!(
+            first
+                .next()
+                .await
+                .unwrap()
+                .unwrap()
+                .message
+                .unwrap()
+                .content,
+            "a"
+        );
+        assert_eq!(api.in_flight(), 1);
+
+        // A cancelled waiter leaves the shared call untouched.
+        let token = CancellationToken::new();
+        let control = RequestControlBuilder::default()
+            .cancellation(token.clone())
+            .build()
+            .unwrap();
+        let cancelled = api.chat_with_control(request("llama3"), control).await;
+        token.cancel();
+        let err = cancelled.unwrap().as_response().await.err().unwrap();
+        assert_eq!(err, OllamaError::Cancelled);
+
+        let late = chat_content(&api, request("llama3")).await;
+        assert_eq!(late, "abc");
+        let mut rest = String::new();
+        while let Some(item) = first.next().await {
+            rest += &item.unwrap().message.unwrap().content;
+        }
+        assert_eq!(rest, "bc");
+        assert_eq!(mock.requests_to("/api/chat").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_embeddings() {
+        let mock = MockOllama::start().await;
+        let api = SingleFlight::new(mock.client());
+        let embeddings = |prompt: &str| EmbeddingsRequest {
+            model: String::from("nomic-embed-text"),
+            prompt: prompt.to_string(),
+            ..Default::default()
+        };
+
+        let (a, b, c) = tokio::join!(
+            api.generate_embeddings(embeddings("a")),
+            api.generate_embeddings(embeddings("a")),
+            api.generate_embeddings(embeddings("c")),
+        );
+        assert_eq!(a.unwrap(), b.unwrap());
+        assert!(c.is_ok());
+        assert_eq!(mock.requests_to("/api/embeddings").len(), 2);
+    }
+}
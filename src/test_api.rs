@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+
+    use crate::{
+        api::OllamaApi,
+        chat_completion::{
+            message::{Message, Role},
+            request::{ChatCompletionRequest, ChatCompletionRequestBuilder},
+            response::ChatResponse,
+        },
+        completion::{request::CompletionRequest, response::CompletionResponse},
+        control::RequestControl,
+        errors::OllamaError,
+        model::{
+            copy::CopyModelRequest,
+            create::{CreateModelRequest, CreateModelResponse},
+            delete::DeleteModelRequest,
+            generate_embeddings::{EmbeddingsRequest, EmbeddingsResponse},
+            list_local::ListLocalModelsResponse,
+            list_running::ListRunningModelsResponse,
+            pull::{PullModelRequest, PullModelResponse},
+            push::{PushModelRequest, PushModelResponse},
+            show_info::{ShowModelRequest, ShowModelResponse},
+        },
+        response::OllamaResponse,
+        testing::MockOllama,
+    };
+
+    /// A fake answering every chat with the same message, and failing every other call.
+    struct FakeOllama;
+
+    fn unsupported() -> OllamaError {
+        OllamaError::InvalidParameter(String::from("not supported by FakeOllama"))
+    }
+
+    #[async_trait]
+    impl OllamaApi for FakeOllama {
+        async fn chat_with_control(
+            &self,
+            request: ChatCompletionRequest,
+            _control: RequestControl,
+        ) -> Result<OllamaResponse<ChatResponse>, OllamaError> {
+            OllamaResponse::from_items(vec![ChatResponse {
+                model: request.model,
+                done: true,
+                message: Some(Message {
+                    role: Role::Assistant,
+                    content: String::from("fake"),
+                    images: None,
+                }),
+                ..Default::default()
+            }])
+        }
+
+        async fn completion_with_control(
+            &self,
+            _request: CompletionRequest,
+            _control: RequestControl,
+        ) -> Result<OllamaResponse<CompletionResponse>, OllamaError> {
+            Err(unsupported())
+        }
+
+        async fn generate_embeddings(
+            &self,
+            _request: EmbeddingsRequest,
+        ) -> Result<EmbeddingsResponse, OllamaError> {
+            Err(unsupported())
+        }
+
+        async fn create(
+            &self,
+            _request: CreateModelRequest,
+        ) -> Result<OllamaResponse<CreateModelResponse>, OllamaError> {
+            Err(unsupported())
+        }
+
+        async fn list_local(&self) -> Result<ListLocalModelsResponse, OllamaError> {
+            Err(unsupported())
+        }
+
+        async fn list_running(&self) -> Result<ListRunningModelsResponse, OllamaError> {
+            Err(unsupported())
+        }
+
+        async fn show_info(
+            &self,
+            _request: ShowModelRequest,
+        ) -> Result<ShowModelResponse, OllamaError> {
+            Err(unsupported())
+        }
+
+        async fn copy(&self, _request: CopyModelRequest) -> Result<(), OllamaError> {
+            Err(unsupported())
+        }
+
+        async fn delete(&self, _request: DeleteModelRequest) -> Result<(), OllamaError> {
+            Err(unsupported())
+        }
+
+        async fn pull(
+            &self,
+            _request: PullModelRequest,
+        ) -> Result<OllamaResponse<PullModelResponse>, OllamaError> {
+            Err(unsupported())
+        }
+
+        async fn push(
+            &self,
+            _request: PushModelRequest,
+        ) -> Result<OllamaResponse<PushModelResponse>, OllamaError> {
+            Err(unsupported())
+        }
+    }
+
+    async fn answer(api: &impl OllamaApi) -> String {
+        let request = ChatCompletionRequestBuilder::default()
+            .model("llama3")
+            .messages(vec![])
+            .build()
+            .unwrap();
+
+        let response = api.chat(request).await.unwrap();
+        response
+            .as_response()
+            .await
+            .unwrap()
+            .message
+            .unwrap()
+            .content
+    }
+
+    #[tokio::test]
+    async fn test_fake_api() {
+        assert_eq!(answer(&FakeOllama).await, "fake");
+
+        let api: Arc<dyn OllamaApi> = Arc::new(FakeOllama);
+        assert_eq!(answer(&api).await, "fake");
+        assert!(matches!(
+            api.list_local().await,
+            Err(OllamaError::InvalidParameter(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_client_api() {
+        let mock = MockOllama::start().await;
+        assert_eq!(answer(&mock.client()).await, "Hello from mock");
+    }
+}
@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod tests {
+    use std::{future::pending, time::Duration};
+
+    use crate::{
+        control::{CancellationToken, RequestControlBuilder},
+        errors::OllamaError,
+    };
+
+    #[tokio::test]
+    async fn test_first_token_timeout() {
+        let control = RequestControlBuilder::default()
+            .first_token_timeout(Duration::from_millis(20))
+            .build()
+            .unwrap();
+
+        let watchdog = control.start();
+        let result = watchdog.run(pending::<()>()).await;
+        assert!(matches!(result, Err(OllamaError::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_idle_timeout() {
+        let control = RequestControlBuilder::default()
+            .idle_timeout(Duration::from_millis(20))
+            .build()
+            .unwrap();
+
+        let watchdog = control.start();
+        let result = watchdog
+            .run(tokio::time::sleep(Duration::from_millis(50)))
+            .await;
+        assert_eq!(result, Ok(()));
+
+        watchdog.touch();
+        let result = watchdog.run(pending::<()>()).await;
+        assert!(matches!(result, Err(OllamaError::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_cancellation() {
+        let token = CancellationToken::new();
+        let control = RequestControlBuilder::default()
+            .cancellation(token.clone())
+            .build()
+            .unwrap();
+
+        let watchdog = control.start();
+        token.cancel();
+        let result = watchdog.run(pending::<()>()).await;
+        assert_eq!(result, Err(OllamaError::Cancelled));
+    }
+}
@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::options::{Options, OptionsBuilder};
+
+    #[test]
+    fn test_names() {
+        let names = Options::names();
+        assert_eq!(names.first(), Some(&"mirostat"));
+        assert!(names.contains(&"presence_penalty"));
+        assert!(names.contains(&"top_p"));
+
+        // Every parameter serializes under its name.
+        let options = OptionsBuilder::default()
+            .mirostat(1)
+            .temperature(0.5)
+            .stop("###")
+            .build()
+            .unwrap();
+        let serialized = serde_json::to_value(&options).unwrap();
+        let keys = serialized.as_object().unwrap().keys();
+        assert!(keys.into_iter().all(|key| names.contains(&key.as_str())));
+    }
+
+    #[test]
+    fn test_options_set() {
+        let mut options = Options::default();
+        options.set("num_predict", "16").unwrap();
+        options.set("top_p", "0.5").unwrap();
+        options.set("stop", "###").unwrap();
+        assert_eq!(options.num_predict, Some(16));
+        assert_eq!(options.top_p, Some(0.5));
+        assert_eq!(options.stop.as_deref(), Some("###"));
+
+        assert!(options.set("unknown", "1").is_err());
+        assert!(options.set("seed", "not a number").is_err());
+        assert_eq!(options.seed, None);
+
+        assert_eq!(options.get("top_p"), Some(json!(0.5)));
+        options.set("top_p", "null").unwrap();
+        assert_eq!(options.get("top_p"), None);
+    }
+}
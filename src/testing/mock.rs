@@ -0,0 +1,378 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_stream::stream;
+use axum::{
+    body::{Body, Bytes},
+    extract::State,
+    http::{header, HeaderMap, Method, StatusCode, Uri},
+    response::Response,
+    Router,
+};
+use serde_json::{json, Value};
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use crate::client::OllamaClient;
+
+/// A scripted response served by [`MockOllama`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockResponse {
+    /// A single JSON object.
+    Json { status: u16, body: Value },
+
+    /// A stream of JSON objects, one per line, each sent as its own chunk
+    /// after waiting `delay`.
+    NdJson { lines: Vec<Value>, delay: Duration },
+
+    /// An error body as returned by Ollama: `{"error": message}`.
+    Error { status: u16, message: String },
+
+    /// A plain text body.
+    Text { status: u16, body: String },
+
+    /// A body sent as the given raw chunks, each after waiting `delay`, for
+    /// bodies whose lines or characters are split across chunks.
+    Chunks {
+        chunks: Vec<Vec<u8>>,
+        delay: Duration,
+    },
+}
+
+impl MockResponse {
+    pub fn json(body: Value) -> Self {
+        Self::Json { status: 200, body }
+    }
+
+    pub fn ndjson(lines: Vec<Value>) -> Self {
+        Self::NdJson {
+            lines,
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Self::Error {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn text(body: impl Into<String>) -> Self {
+        Self::Text {
+            status: 200,
+            body: body.into(),
+        }
+    }
+
+    pub fn chunks(chunks: Vec<Vec<u8>>) -> Self {
+        Self::Chunks {
+            chunks,
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Wait `delay` before sending each line of an NDJSON stream, or each chunk.
+    pub fn with_delay(self, delay: Duration) -> Self {
+        match self {
+            Self::NdJson { lines, .. } => Self::NdJson { lines, delay },
+            Self::Chunks { chunks, .. } => Self::Chunks { chunks, delay },
+            other => other,
+        }
+    }
+
+    /// A `/api/chat` stream with one chunk per part and a final chunk holding statistics.
+    pub fn chat_stream(model: &str, parts: &[&str]) -> Self {
+        let mut lines: Vec<Value> = parts
+            .iter()
+            .map(|part| {
+                json!({
+                    "model": model,
+                    "created_at": MOCK_CREATED_AT,
+                    "message": { "role": "assistant", "content": part },
+                    "done": false,
+                })
+            })
+            .collect();
+        lines.push(json!({
+            "model": model,
+            "created_at": MOCK_CREATED_AT,
+            "message": { "role": "assistant", "content": "" },
+            "done": true,
+            "total_duration": 5_000_000,
+            "load_duration": 1_000_000,
+            "prompt_eval_count": 10,
+            "prompt_eval_duration": 1_000_000,
+            "eval_count": parts.len(),
+            "eval_duration": 2_000_000,
+        }));
+        Self::ndjson(lines)
+    }
+
+    /// A `/api/generate` stream with one chunk per part and a final chunk holding `context`.
+    pub fn completion_stream(model: &str, parts: &[&str], context: Vec<usize>) -> Self {
+        let mut lines: Vec<Value> = parts
+            .iter()
+            .map(|part| {
+                json!({
+                    "model": model,
+                    "created_at": MOCK_CREATED_AT,
+                    "response": part,
+                    "done": false,
+                })
+            })
+            .collect();
+        lines.push(json!({
+            "model": model,
+            "created_at": MOCK_CREATED_AT,
+            "response": "",
+            "done": true,
+            "context": context,
+            "total_duration": 5_000_000,
+            "load_duration": 1_000_000,
+            "prompt_eval_count": 10,
+            "prompt_eval_duration": 1_000_000,
+            "eval_count": parts.len(),
+            "eval_duration": 2_000_000,
+        }));
+        Self::ndjson(lines)
+    }
+}
+
+/// A request received by [`MockOllama`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: HeaderMap,
+    pub body: Option<Value>,
+}
+
+const MOCK_CREATED_AT: &str = "2024-06-01T00:00:00.000000Z";
+
+#[derive(Default)]
+struct MockState {
+    scripted: HashMap<String, VecDeque<MockResponse>>,
+    requests: Vec<RecordedRequest>,
+}
+
+/// An in-process Ollama server bound to a random local port. Every endpoint answers
+/// with a plausible default response, which can be overridden per path with
+/// [`MockOllama::respond`]. Received requests are recorded for assertions.
+pub struct MockOllama {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    server: JoinHandle<()>,
+}
+
+impl MockOllama {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(MockState::default()));
+
+        let router = Router::new().fallback(handle).with_state(state.clone());
+        let server = tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+
+        Self {
+            addr,
+            state,
+            server,
+        }
+    }
+
+    /// Base URL of the server, e.g. `http://127.0.0.1:38211`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// A client sending its requests to this server.
+    pub fn client(&self) -> OllamaClient {
+        OllamaClient::new(self.url())
+    }
+
+    /// Serve `response` to the next request to `path`. Responses queued for the same
+    /// path are served in order, then the default response is used again.
+    pub fn respond(&self, path: &str, response: MockResponse) -> &Self {
+        self.state
+            .lock()
+            .unwrap()
+            .scripted
+            .entry(path.to_string())
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// All requests received so far.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Requests received so far on `path`.
+    pub fn requests_to(&self, path: &str) -> Vec<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|request| request.path == path)
+            .collect()
+    }
+}
+
+impl Drop for MockOllama {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+async fn handle(
+    State(state): State<Arc<Mutex<MockState>>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let path = uri.path().to_string();
+    let body: Option<Value> = serde_json::from_slice(&body).ok();
+
+    let scripted = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(RecordedRequest {
+            method: method.to_string(),
+            path: path.clone(),
+            headers,
+            body: body.clone(),
+        });
+        state.scripted.get_mut(&path).and_then(VecDeque::pop_front)
+    };
+
+    let response = scripted.unwrap_or_else(|| default_response(&method, &path, body.as_ref()));
+    into_response(response)
+}
+
+fn default_response(method: &Method, path: &str, body: Option<&Value>) -> MockResponse {
+    let model = body
+        .and_then(|body| body.get("model").or_else(|| body.get("name")))
+        .and_then(Value::as_str)
+        .unwrap_or("mock")
+        .to_string();
+    let streaming = body
+        .and_then(|body| body.get("stream"))
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+
+    let response = match (method.as_str(), path) {
+        ("GET", "/") | ("HEAD", "/") => MockResponse::text("Ollama is running"),
+        ("GET", "/api/version") => MockResponse::json(json!({ "version": "0.0.0-mock" })),
+        ("POST", "/api/chat") => MockResponse::chat_stream(&model, &["Hello", " from", " mock"]),
+        ("POST", "/api/generate") => {
+            MockResponse::completion_stream(&model, &["Hello", " from", " mock"], vec![1, 2, 3])
+        }
+        ("POST", "/api/embeddings") => MockResponse::json(json!({ "embedding": [0.1, 0.2, 0.3] })),
+        ("POST", "/api/embed") => MockResponse::json(json!({
+            "model": model,
+            "embeddings": [[0.1, 0.2, 0.3]],
+            "prompt_eval_count": 1,
+        })),
+        ("POST", "/api/pull") | ("POST", "/api/push") | ("POST", "/api/create") => {
+            MockResponse::ndjson(vec![
+                json!({ "status": "pulling manifest" }),
+                json!({ "status": "writing manifest" }),
+                json!({ "status": "success" }),
+            ])
+        }
+        ("GET", "/api/tags") | ("GET", "/api/ps") => MockResponse::json(json!({ "models": [] })),
+        ("POST", "/api/show") => MockResponse::json(json!({
+            "modelfile": format!("FROM {model}"),
+            "parameters": "",
+            "template": "{{ .Prompt }}",
+            "details": { "format": "gguf", "family": "llama" },
+        })),
+        ("POST", "/api/copy") | ("DELETE", "/api/delete") => MockResponse::text(""),
+        _ => MockResponse::error(404, "404 page not found"),
+    };
+
+    if streaming {
+        return response;
+    }
+    match response {
+        MockResponse::NdJson { lines, .. } => MockResponse::json(merge_lines(lines)),
+        other => other,
+    }
+}
+
+/// The single object returned by streaming endpoints called with `"stream": false`.
+fn merge_lines(lines: Vec<Value>) -> Value {
+    let mut content = String::default();
+    let mut response = String::default();
+    for line in &lines {
+        if let Some(part) = line.pointer("/message/content").and_then(Value::as_str) {
+            content += part;
+        }
+        if let Some(part) = line.get("response").and_then(Value::as_str) {
+            response += part;
+        }
+    }
+
+    let mut last = lines.into_iter().last().unwrap_or(Value::Null);
+    if let Some(message) = last.get_mut("message") {
+        message["content"] = Value::String(content);
+    }
+    if last.get("response").is_some() {
+        last["response"] = Value::String(response);
+    }
+    last
+}
+
+fn into_response(response: MockResponse) -> Response {
+    let builder = Response::builder();
+    let response = match response {
+        MockResponse::Json { status, body } => builder
+            .status(status)
+            .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+            .body(Body::from(body.to_string())),
+        MockResponse::Error { status, message } => builder
+            .status(status)
+            .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+            .body(Body::from(json!({ "error": message }).to_string())),
+        MockResponse::Text { status, body } => builder
+            .status(status)
+            .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+            .body(Body::from(body)),
+        MockResponse::NdJson { lines, delay } => {
+            let chunks = stream! {
+                for line in lines {
+                    if !delay.is_zero() {
+                        tokio::time::sleep(delay).await;
+                    }
+                    yield Ok::<_, Infallible>(Bytes::from(format!("{line}\n")));
+                }
+            };
+            builder
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, "application/x-ndjson")
+                .body(Body::from_stream(chunks))
+        }
+        MockResponse::Chunks { chunks, delay } => {
+            let chunks = stream! {
+                for chunk in chunks {
+                    if !delay.is_zero() {
+                        tokio::time::sleep(delay).await;
+                    }
+                    yield Ok::<_, Infallible>(Bytes::from(chunk));
+                }
+            };
+            builder
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, "application/x-ndjson")
+                .body(Body::from_stream(chunks))
+        }
+    };
+
+    response.unwrap()
+}
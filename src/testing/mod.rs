@@ -0,0 +1,21 @@
+//! Offline test helpers, available with the `testing` feature.
+
+pub mod mock;
+
+pub use mock::{MockOllama, MockResponse, RecordedRequest};
+
+/// The content of the response to the chat `request`, read to the end.
+#[cfg(test)]
+pub(crate) async fn chat_content(
+    api: &impl crate::api::OllamaApi,
+    request: crate::chat_completion::request::ChatCompletionRequest,
+) -> String {
+    let response = api.chat(request).await.unwrap();
+    response
+        .as_response()
+        .await
+        .unwrap()
+        .message
+        .unwrap()
+        .content
+}